@@ -3,7 +3,7 @@
 use criterion::{criterion_group, Criterion};
 
 mod dilithium_benches {
-	use qp_rusty_crystals_dilithium::ml_dsa_87::Keypair;
+	use qp_rusty_crystals_dilithium::ml_dsa_87::{BatchItem, Keypair};
 
 	use super::*;
 
@@ -14,28 +14,85 @@ mod dilithium_benches {
 	}
 
 	fn sign(c: &mut Criterion) {
-		let keypair = Keypair::generate(None);
+		let keypair = Keypair::generate(None).unwrap();
 		let msg = b"";
 
 		c.bench_function("Dilithium signing", move |b| b.iter(|| keypair.sign(msg, None, false)));
 	}
 
+	fn sign_prepared(c: &mut Criterion) {
+		let prepared = Keypair::generate(None).unwrap().secret.prepare();
+		let msg = b"";
+
+		c.bench_function("Dilithium signing (prepared key)", move |b| {
+			b.iter(|| prepared.sign(msg, None, false))
+		});
+	}
+
 	fn verify(c: &mut Criterion) {
-		let keypair = Keypair::generate(None);
+		let keypair = Keypair::generate(None).unwrap();
 		let msg = b"";
-		let sig = keypair.sign(msg, None, false);
+		let sig = keypair.sign(msg, None, false).unwrap();
 
 		c.bench_function("Dilithium signature verification", move |b| {
 			b.iter(|| keypair.verify(msg, sig.as_slice(), None))
 		});
 	}
 
+	fn prepare_public_key(c: &mut Criterion) {
+		let keypair = Keypair::generate(None).unwrap();
+
+		c.bench_function("Dilithium public key preparation", move |b| {
+			b.iter(|| keypair.public.prepare())
+		});
+	}
+
+	fn verify_prepared(c: &mut Criterion) {
+		let keypair = Keypair::generate(None).unwrap();
+		let prepared = keypair.public.prepare();
+		let msg = b"";
+		let sig = keypair.sign(msg, None, false).unwrap();
+
+		c.bench_function("Dilithium signature verification (prepared key)", move |b| {
+			b.iter(|| prepared.verify(msg, sig.as_slice(), None))
+		});
+	}
+
+	fn verify_batch(c: &mut Criterion) {
+		let keys: Vec<Keypair> = (0..4).map(|_| Keypair::generate(None).unwrap()).collect();
+		let msgs: Vec<[u8; 32]> = (0..256u32).map(|i| [i as u8; 32]).collect();
+		let sigs: Vec<_> = msgs
+			.iter()
+			.enumerate()
+			.map(|(i, m)| keys[i % 4].sign(m, None, false).unwrap())
+			.collect();
+		let items: Vec<BatchItem> = (0..msgs.len())
+			.map(|i| BatchItem {
+				public: &keys[i % 4].public,
+				msg: &msgs[i],
+				ctx: None,
+				sig: &sigs[i],
+			})
+			.collect();
+
+		c.bench_function("Dilithium batch verification (256 signatures, 4 keys)", |b| {
+			b.iter(|| qp_rusty_crystals_dilithium::verify_batch(&items))
+		});
+		c.bench_function("Dilithium one-by-one verification (256 signatures, 4 keys)", |b| {
+			b.iter(|| items.iter().all(|item| item.public.verify(item.msg, item.sig, None).is_ok()))
+		});
+	}
+
 	criterion_group! {
 		name = dilithium_benches;
 		config = Criterion::default();
 		targets =
 			sign,
+			sign_prepared,
 			verify,
+			prepare_public_key,
+			verify_prepared,
+			verify_batch,
 			key_generation,
 	}
 }
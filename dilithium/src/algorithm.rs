@@ -0,0 +1,415 @@
+//! Runtime selection of the ML-DSA parameter set.
+//!
+//! [Algorithm] names a parameter set and carries its identifiers and sizes. [AnyKeypair],
+//! [AnyPublicKey] and [AnySignature] hold a key or signature of any level and dispatch to the
+//! matching [crate::ml_dsa] implementation, for code that learns the level from its input.
+//!
+//! Tagged encodings prefix the raw bytes with one byte, the last arc of the algorithm OID
+//! (0x11, 0x12 and 0x13 for ML-DSA-44, -65 and -87).
+
+use core::{fmt, str::FromStr};
+
+use crate::{
+	errors::{Error, KeyParsingError},
+	ml_dsa::{Keypair, MlDsa44, MlDsa65, MlDsa87, ParameterSet, PublicKey, Signature},
+};
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+/// An ML-DSA parameter set.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Algorithm {
+	MlDsa44,
+	MlDsa65,
+	MlDsa87,
+}
+
+impl Algorithm {
+	/// All supported algorithms, weakest first.
+	pub const ALL: [Algorithm; 3] = [Algorithm::MlDsa44, Algorithm::MlDsa65, Algorithm::MlDsa87];
+
+	/// Name as used by FIPS 204, e.g. "ML-DSA-65".
+	pub const fn name(self) -> &'static str {
+		match self {
+			Algorithm::MlDsa44 => "ML-DSA-44",
+			Algorithm::MlDsa65 => "ML-DSA-65",
+			Algorithm::MlDsa87 => "ML-DSA-87",
+		}
+	}
+
+	/// Object identifier in dotted form, from the NIST CSOR sigAlgs arc.
+	pub const fn oid(self) -> &'static str {
+		match self {
+			Algorithm::MlDsa44 => "2.16.840.1.101.3.4.3.17",
+			Algorithm::MlDsa65 => "2.16.840.1.101.3.4.3.18",
+			Algorithm::MlDsa87 => "2.16.840.1.101.3.4.3.19",
+		}
+	}
+
+	/// DER encoding of [Algorithm::oid], including the tag and length bytes.
+	pub const fn oid_der(self) -> [u8; 11] {
+		[0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x03, self.tag()]
+	}
+
+	/// Prefix byte of the tagged encodings; the last arc of the OID.
+	pub const fn tag(self) -> u8 {
+		match self {
+			Algorithm::MlDsa44 => 0x11,
+			Algorithm::MlDsa65 => 0x12,
+			Algorithm::MlDsa87 => 0x13,
+		}
+	}
+
+	/// NIST security category (2, 3 or 5).
+	pub const fn security_category(self) -> u8 {
+		match self {
+			Algorithm::MlDsa44 => 2,
+			Algorithm::MlDsa65 => 3,
+			Algorithm::MlDsa87 => 5,
+		}
+	}
+
+	pub const fn public_key_bytes(self) -> usize {
+		match self {
+			Algorithm::MlDsa44 => MlDsa44::PUBLICKEYBYTES,
+			Algorithm::MlDsa65 => MlDsa65::PUBLICKEYBYTES,
+			Algorithm::MlDsa87 => MlDsa87::PUBLICKEYBYTES,
+		}
+	}
+
+	pub const fn secret_key_bytes(self) -> usize {
+		match self {
+			Algorithm::MlDsa44 => MlDsa44::SECRETKEYBYTES,
+			Algorithm::MlDsa65 => MlDsa65::SECRETKEYBYTES,
+			Algorithm::MlDsa87 => MlDsa87::SECRETKEYBYTES,
+		}
+	}
+
+	pub const fn signature_bytes(self) -> usize {
+		match self {
+			Algorithm::MlDsa44 => MlDsa44::SIGNBYTES,
+			Algorithm::MlDsa65 => MlDsa65::SIGNBYTES,
+			Algorithm::MlDsa87 => MlDsa87::SIGNBYTES,
+		}
+	}
+
+	pub const fn keypair_bytes(self) -> usize {
+		self.secret_key_bytes() + self.public_key_bytes()
+	}
+
+	/// Look up an algorithm by the prefix byte of a tagged encoding.
+	pub fn from_tag(tag: u8) -> Option<Algorithm> {
+		Algorithm::ALL.into_iter().find(|alg| alg.tag() == tag)
+	}
+
+	/// Look up an algorithm by its dotted OID.
+	pub fn from_oid(oid: &str) -> Option<Algorithm> {
+		Algorithm::ALL.into_iter().find(|alg| alg.oid() == oid)
+	}
+
+	/// Look up an algorithm by its DER encoded OID.
+	pub fn from_oid_der(der: &[u8]) -> Option<Algorithm> {
+		Algorithm::ALL.into_iter().find(|alg| alg.oid_der() == der)
+	}
+}
+
+impl fmt::Display for Algorithm {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(self.name())
+	}
+}
+
+/// Parses a name ("ML-DSA-87", case-insensitive) or a dotted OID.
+impl FromStr for Algorithm {
+	type Err = KeyParsingError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		Algorithm::ALL
+			.into_iter()
+			.find(|alg| alg.name().eq_ignore_ascii_case(s) || alg.oid() == s)
+			.ok_or(KeyParsingError::UnknownAlgorithm)
+	}
+}
+
+/// Split a tagged encoding into its algorithm and the raw bytes.
+fn untag(bytes: &[u8]) -> Result<(Algorithm, &[u8]), KeyParsingError> {
+	match bytes.split_first() {
+		Some((&tag, rest)) =>
+			Ok((Algorithm::from_tag(tag).ok_or(KeyParsingError::UnknownAlgorithm)?, rest)),
+		None => Err(KeyParsingError::UnknownAlgorithm),
+	}
+}
+
+#[cfg(feature = "alloc")]
+fn tagged(alg: Algorithm, bytes: &[u8]) -> Vec<u8> {
+	let mut out = Vec::with_capacity(1 + bytes.len());
+	out.push(alg.tag());
+	out.extend_from_slice(bytes);
+	out
+}
+
+/// Evaluate $body with $inner bound to the level specific value held by $value.
+macro_rules! dispatch {
+	($enum:ident, $value:expr, $inner:ident => $body:expr) => {
+		match $value {
+			$enum::MlDsa44($inner) => $body,
+			$enum::MlDsa65($inner) => $body,
+			$enum::MlDsa87($inner) => $body,
+		}
+	};
+}
+
+/// A keypair of any ML-DSA level.
+#[derive(Clone, Debug)]
+pub enum AnyKeypair {
+	MlDsa44(Keypair<MlDsa44>),
+	MlDsa65(Keypair<MlDsa65>),
+	MlDsa87(Keypair<MlDsa87>),
+}
+
+impl AnyKeypair {
+	/// Generate a keypair for `alg`; see [Keypair::generate].
+	pub fn generate(alg: Algorithm, entropy: Option<&[u8]>) -> Result<AnyKeypair, Error> {
+		Ok(match alg {
+			Algorithm::MlDsa44 => AnyKeypair::MlDsa44(Keypair::generate(entropy)?),
+			Algorithm::MlDsa65 => AnyKeypair::MlDsa65(Keypair::generate(entropy)?),
+			Algorithm::MlDsa87 => AnyKeypair::MlDsa87(Keypair::generate(entropy)?),
+		})
+	}
+
+	pub fn algorithm(&self) -> Algorithm {
+		match self {
+			AnyKeypair::MlDsa44(_) => Algorithm::MlDsa44,
+			AnyKeypair::MlDsa65(_) => Algorithm::MlDsa65,
+			AnyKeypair::MlDsa87(_) => Algorithm::MlDsa87,
+		}
+	}
+
+	pub fn public_key(&self) -> AnyPublicKey {
+		match self {
+			AnyKeypair::MlDsa44(k) => AnyPublicKey::MlDsa44(k.public.clone()),
+			AnyKeypair::MlDsa65(k) => AnyPublicKey::MlDsa65(k.public.clone()),
+			AnyKeypair::MlDsa87(k) => AnyPublicKey::MlDsa87(k.public.clone()),
+		}
+	}
+
+	/// Raw secret and public key bytes, as [Keypair::to_bytes].
+	#[cfg(feature = "alloc")]
+	pub fn to_bytes(&self) -> Vec<u8> {
+		dispatch!(AnyKeypair, self, k => k.to_bytes().as_ref().to_vec())
+	}
+
+	/// Raw bytes prefixed with the algorithm tag.
+	#[cfg(feature = "alloc")]
+	pub fn to_tagged_bytes(&self) -> Vec<u8> {
+		tagged(self.algorithm(), &self.to_bytes())
+	}
+
+	/// Parse raw keypair bytes of a known algorithm.
+	pub fn from_bytes(alg: Algorithm, bytes: &[u8]) -> Result<AnyKeypair, KeyParsingError> {
+		Ok(match alg {
+			Algorithm::MlDsa44 => AnyKeypair::MlDsa44(Keypair::from_bytes(bytes)?),
+			Algorithm::MlDsa65 => AnyKeypair::MlDsa65(Keypair::from_bytes(bytes)?),
+			Algorithm::MlDsa87 => AnyKeypair::MlDsa87(Keypair::from_bytes(bytes)?),
+		})
+	}
+
+	/// Parse the output of [AnyKeypair::to_tagged_bytes].
+	pub fn from_tagged_bytes(bytes: &[u8]) -> Result<AnyKeypair, KeyParsingError> {
+		let (alg, bytes) = untag(bytes)?;
+		AnyKeypair::from_bytes(alg, bytes)
+	}
+
+	/// Compute a signature for a given message; see [Keypair::sign].
+	pub fn sign(
+		&self,
+		msg: &[u8],
+		ctx: Option<&[u8]>,
+		hedged: bool,
+	) -> Result<AnySignature, Error> {
+		Ok(match self {
+			AnyKeypair::MlDsa44(k) => AnySignature::MlDsa44(k.sign(msg, ctx, hedged)?),
+			AnyKeypair::MlDsa65(k) => AnySignature::MlDsa65(k.sign(msg, ctx, hedged)?),
+			AnyKeypair::MlDsa87(k) => AnySignature::MlDsa87(k.sign(msg, ctx, hedged)?),
+		})
+	}
+
+	/// Verify a signature with the public half of this keypair; see [AnyPublicKey::verify].
+	pub fn verify(&self, msg: &[u8], sig: &AnySignature, ctx: Option<&[u8]>) -> Result<(), Error> {
+		self.public_key().verify(msg, sig, ctx)
+	}
+}
+
+/// A public key of any ML-DSA level.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum AnyPublicKey {
+	MlDsa44(PublicKey<MlDsa44>),
+	MlDsa65(PublicKey<MlDsa65>),
+	MlDsa87(PublicKey<MlDsa87>),
+}
+
+impl AnyPublicKey {
+	pub fn algorithm(&self) -> Algorithm {
+		match self {
+			AnyPublicKey::MlDsa44(_) => Algorithm::MlDsa44,
+			AnyPublicKey::MlDsa65(_) => Algorithm::MlDsa65,
+			AnyPublicKey::MlDsa87(_) => Algorithm::MlDsa87,
+		}
+	}
+
+	pub fn as_bytes(&self) -> &[u8] {
+		dispatch!(AnyPublicKey, self, k => k.bytes.as_ref())
+	}
+
+	/// Raw bytes prefixed with the algorithm tag.
+	#[cfg(feature = "alloc")]
+	pub fn to_tagged_bytes(&self) -> Vec<u8> {
+		tagged(self.algorithm(), self.as_bytes())
+	}
+
+	/// Parse raw public key bytes of a known algorithm.
+	pub fn from_bytes(alg: Algorithm, bytes: &[u8]) -> Result<AnyPublicKey, KeyParsingError> {
+		Ok(match alg {
+			Algorithm::MlDsa44 => AnyPublicKey::MlDsa44(PublicKey::from_bytes(bytes)?),
+			Algorithm::MlDsa65 => AnyPublicKey::MlDsa65(PublicKey::from_bytes(bytes)?),
+			Algorithm::MlDsa87 => AnyPublicKey::MlDsa87(PublicKey::from_bytes(bytes)?),
+		})
+	}
+
+	/// Parse the output of [AnyPublicKey::to_tagged_bytes].
+	pub fn from_tagged_bytes(bytes: &[u8]) -> Result<AnyPublicKey, KeyParsingError> {
+		let (alg, bytes) = untag(bytes)?;
+		AnyPublicKey::from_bytes(alg, bytes)
+	}
+
+	/// Verify a signature for a given message.
+	///
+	/// Returns [Error::AlgorithmMismatch] if the signature belongs to a different algorithm than
+	/// the key.
+	pub fn verify(&self, msg: &[u8], sig: &AnySignature, ctx: Option<&[u8]>) -> Result<(), Error> {
+		match (self, sig) {
+			(AnyPublicKey::MlDsa44(k), AnySignature::MlDsa44(s)) => k.verify(msg, s, ctx),
+			(AnyPublicKey::MlDsa65(k), AnySignature::MlDsa65(s)) => k.verify(msg, s, ctx),
+			(AnyPublicKey::MlDsa87(k), AnySignature::MlDsa87(s)) => k.verify(msg, s, ctx),
+			_ => Err(Error::AlgorithmMismatch),
+		}
+	}
+}
+
+/// A signature of any ML-DSA level.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AnySignature {
+	MlDsa44(Signature<MlDsa44>),
+	MlDsa65(Signature<MlDsa65>),
+	MlDsa87(Signature<MlDsa87>),
+}
+
+impl AnySignature {
+	pub fn algorithm(&self) -> Algorithm {
+		match self {
+			AnySignature::MlDsa44(_) => Algorithm::MlDsa44,
+			AnySignature::MlDsa65(_) => Algorithm::MlDsa65,
+			AnySignature::MlDsa87(_) => Algorithm::MlDsa87,
+		}
+	}
+
+	pub fn as_bytes(&self) -> &[u8] {
+		dispatch!(AnySignature, self, s => s.as_slice())
+	}
+
+	/// Raw bytes prefixed with the algorithm tag.
+	#[cfg(feature = "alloc")]
+	pub fn to_tagged_bytes(&self) -> Vec<u8> {
+		tagged(self.algorithm(), self.as_bytes())
+	}
+
+	/// Wrap raw signature bytes of a known algorithm.
+	pub fn from_bytes(alg: Algorithm, bytes: &[u8]) -> Result<AnySignature, KeyParsingError> {
+		let bad = |_| KeyParsingError::BadSignature;
+		Ok(match alg {
+			Algorithm::MlDsa44 => AnySignature::MlDsa44(bytes.try_into().map_err(bad)?),
+			Algorithm::MlDsa65 => AnySignature::MlDsa65(bytes.try_into().map_err(bad)?),
+			Algorithm::MlDsa87 => AnySignature::MlDsa87(bytes.try_into().map_err(bad)?),
+		})
+	}
+
+	/// Parse the output of [AnySignature::to_tagged_bytes].
+	pub fn from_tagged_bytes(bytes: &[u8]) -> Result<AnySignature, KeyParsingError> {
+		let (alg, bytes) = untag(bytes)?;
+		AnySignature::from_bytes(alg, bytes)
+	}
+}
+
+#[cfg(test)]
+#[cfg(feature = "std")]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn algorithm_properties() {
+		for (alg, p) in Algorithm::ALL.into_iter().zip([
+			(MlDsa44::PUBLICKEYBYTES, MlDsa44::SECRETKEYBYTES, MlDsa44::SIGNBYTES),
+			(MlDsa65::PUBLICKEYBYTES, MlDsa65::SECRETKEYBYTES, MlDsa65::SIGNBYTES),
+			(MlDsa87::PUBLICKEYBYTES, MlDsa87::SECRETKEYBYTES, MlDsa87::SIGNBYTES),
+		]) {
+			assert_eq!((alg.public_key_bytes(), alg.secret_key_bytes(), alg.signature_bytes()), p);
+			assert_eq!(alg.name().parse::<Algorithm>().unwrap(), alg);
+			assert_eq!(alg.oid().parse::<Algorithm>().unwrap(), alg);
+			assert_eq!(Algorithm::from_oid_der(&alg.oid_der()), Some(alg));
+			assert_eq!(Algorithm::from_tag(alg.tag()), Some(alg));
+		}
+		assert_eq!(Algorithm::MlDsa65.to_string(), "ML-DSA-65");
+		assert_eq!("ml-dsa-87".parse::<Algorithm>().unwrap(), Algorithm::MlDsa87);
+		assert_eq!(Algorithm::MlDsa87.security_category(), 5);
+		assert!("ML-DSA-128".parse::<Algorithm>().is_err());
+		assert_eq!(Algorithm::from_tag(0x14), None);
+	}
+
+	#[test]
+	fn tagged_round_trip() {
+		for alg in Algorithm::ALL {
+			let keys = AnyKeypair::generate(alg, None).unwrap();
+			assert_eq!(keys.algorithm(), alg);
+			let msg = b"algorithm agile";
+			let sig = keys.sign(msg, Some(b"ctx"), true).unwrap();
+			assert_eq!(sig.as_bytes().len(), alg.signature_bytes());
+
+			let keys = AnyKeypair::from_tagged_bytes(&keys.to_tagged_bytes()).unwrap();
+			let pk = AnyPublicKey::from_tagged_bytes(&keys.public_key().to_tagged_bytes()).unwrap();
+			let sig = AnySignature::from_tagged_bytes(&sig.to_tagged_bytes()).unwrap();
+			assert_eq!(pk, keys.public_key());
+			assert!(pk.verify(msg, &sig, Some(b"ctx")).is_ok());
+			assert!(keys.verify(msg, &sig, Some(b"ctx")).is_ok());
+			assert_eq!(pk.verify(b"other", &sig, Some(b"ctx")), Err(Error::ChallengeMismatch));
+		}
+	}
+
+	#[test]
+	fn mismatched_algorithms() {
+		let keys_44 = AnyKeypair::generate(Algorithm::MlDsa44, None).unwrap();
+		let keys_65 = AnyKeypair::generate(Algorithm::MlDsa65, None).unwrap();
+		let sig = keys_44.sign(b"msg", None, false).unwrap();
+		assert_eq!(keys_65.verify(b"msg", &sig, None), Err(Error::AlgorithmMismatch));
+
+		// The tag decides how the rest is parsed, so a relabelled key is rejected by length.
+		let mut bytes = keys_44.public_key().to_tagged_bytes();
+		bytes[0] = Algorithm::MlDsa65.tag();
+		assert!(matches!(
+			AnyPublicKey::from_tagged_bytes(&bytes),
+			Err(KeyParsingError::BadPublicKey)
+		));
+		bytes[0] = 0;
+		assert!(matches!(
+			AnyPublicKey::from_tagged_bytes(&bytes),
+			Err(KeyParsingError::UnknownAlgorithm)
+		));
+		assert!(matches!(
+			AnySignature::from_tagged_bytes(&[]),
+			Err(KeyParsingError::UnknownAlgorithm)
+		));
+		assert!(matches!(
+			AnySignature::from_bytes(Algorithm::MlDsa87, sig.as_bytes()),
+			Err(KeyParsingError::BadSignature)
+		));
+	}
+}
@@ -0,0 +1,144 @@
+//! Verification of many signatures at once.
+//!
+//! [verify_batch] checks a list of (public key, message, context, signature) entries. Entries are
+//! grouped by public key, and each key that signed more than one entry is expanded once into a
+//! [crate::PreparedPublicKey] that serves the whole group. With the `parallel` feature the groups,
+//! and the entries within a group, are spread over the rayon thread pool.
+//!
+//! Every entry is checked individually, so the result lists exactly the entries that failed.
+
+use alloc::{collections::BTreeMap, vec, vec::Vec};
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::{
+	errors::Error,
+	ml_dsa::{ParameterSet, PublicKey},
+};
+
+/// One signature to check in a batch.
+#[derive(Clone, Copy, Debug)]
+pub struct BatchItem<'a, P: ParameterSet> {
+	pub public: &'a PublicKey<P>,
+	pub msg: &'a [u8],
+	pub ctx: Option<&'a [u8]>,
+	pub sig: &'a [u8],
+}
+
+/// Verify every entry of `items`; see [PublicKey::verify].
+///
+/// Returns Ok(()) if all signatures are valid, otherwise the index of each failing entry in
+/// `items` with the reason it failed, in ascending order of index.
+pub fn verify_batch<P: ParameterSet>(
+	items: &[BatchItem<'_, P>],
+) -> Result<(), Vec<(usize, Error)>> {
+	let mut groups: BTreeMap<&P::PublicKeyBytes, Vec<usize>> = BTreeMap::new();
+	for (i, item) in items.iter().enumerate() {
+		groups.entry(&item.public.bytes).or_default().push(i);
+	}
+	let groups: Vec<Vec<usize>> = groups.into_values().collect();
+
+	#[cfg(feature = "parallel")]
+	let mut failures: Vec<(usize, Error)> =
+		groups.par_iter().flat_map_iter(|group| verify_group(items, group)).collect();
+	#[cfg(not(feature = "parallel"))]
+	let mut failures: Vec<(usize, Error)> =
+		groups.iter().flat_map(|group| verify_group(items, group)).collect();
+
+	if failures.is_empty() {
+		return Ok(());
+	}
+	failures.sort_unstable_by_key(|(i, _)| *i);
+	Err(failures)
+}
+
+/// Verify the entries at `group`, which all share one public key.
+fn verify_group<P: ParameterSet>(
+	items: &[BatchItem<'_, P>],
+	group: &[usize],
+) -> Vec<(usize, Error)> {
+	let public = items[group[0]].public;
+	// A key used once gains nothing from being prepared.
+	if group.len() == 1 {
+		let item = &items[group[0]];
+		return match public.verify(item.msg, item.sig, item.ctx) {
+			Ok(()) => Vec::new(),
+			Err(e) => vec![(group[0], e)],
+		};
+	}
+
+	let prepared = public.prepare();
+	let check = |&i: &usize| {
+		let item = &items[i];
+		prepared.verify(item.msg, item.sig, item.ctx).err().map(|e| (i, e))
+	};
+	#[cfg(feature = "parallel")]
+	return group.par_iter().filter_map(check).collect();
+	#[cfg(not(feature = "parallel"))]
+	group.iter().filter_map(check).collect()
+}
+
+#[cfg(test)]
+#[cfg(feature = "std")]
+mod tests {
+	use super::*;
+	use crate::ml_dsa::{Keypair, MlDsa44, MlDsa65, MlDsa87, Signature};
+
+	/// Entry i is message i signed by key i % 3.
+	fn batch<'a, P: ParameterSet>(
+		keys: &'a [Keypair<P>],
+		msgs: &'a [[u8; 16]],
+		sigs: &'a [Signature<P>],
+	) -> Vec<BatchItem<'a, P>> {
+		sigs.iter()
+			.enumerate()
+			.map(|(i, sig)| BatchItem {
+				public: &keys[i % 3].public,
+				msg: &msgs[i],
+				ctx: Some(b"ctx"),
+				sig: sig.as_ref(),
+			})
+			.collect()
+	}
+
+	fn reports_failures<P: ParameterSet>() {
+		let keys: Vec<Keypair<P>> =
+			(0..3u8).map(|i| Keypair::generate(Some(&[i; 32])).unwrap()).collect();
+		let msgs: Vec<[u8; 16]> = (0..10u8).map(|i| [i; 16]).collect();
+		let mut sigs: Vec<Signature<P>> = msgs
+			.iter()
+			.enumerate()
+			.map(|(i, msg)| keys[i % 3].sign(msg, Some(b"ctx"), false).unwrap())
+			.collect();
+
+		assert_eq!(verify_batch(&batch(&keys, &msgs, &sigs)), Ok(()));
+		assert_eq!(verify_batch::<P>(&[]), Ok(()));
+
+		// Entry 4 signed by the wrong key, entry 7 with a short signature, entry 9 with the wrong
+		// context.
+		sigs[4] = keys[0].sign(&msgs[4], Some(b"ctx"), false).unwrap();
+		let mut items = batch(&keys, &msgs, &sigs);
+		items[7].sig = &items[7].sig[1..];
+		items[9].ctx = None;
+		assert_eq!(
+			verify_batch(&items),
+			Err(vec![
+				(4, Error::ChallengeMismatch),
+				(7, Error::BadLength { expected: P::SIGNBYTES, actual: P::SIGNBYTES - 1 }),
+				(9, Error::ChallengeMismatch),
+			])
+		);
+
+		// A key used only once takes the unprepared path.
+		assert_eq!(verify_batch(&items[9..]), Err(vec![(0, Error::ChallengeMismatch)]));
+		assert_eq!(verify_batch(&items[8..9]), Ok(()));
+	}
+
+	#[test]
+	fn reports_failures_all_levels() {
+		reports_failures::<MlDsa44>();
+		reports_failures::<MlDsa65>();
+		reports_failures::<MlDsa87>();
+	}
+}
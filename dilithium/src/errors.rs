@@ -5,6 +5,10 @@ pub enum KeyParsingError {
 	BadSecretKey,
 	BadPublicKey,
 	BadKeypair,
+	BadSignature,
+	UnknownAlgorithm,
+	/// A key generation seed does not generate the keys it was paired with.
+	SeedMismatch,
 }
 
 impl Display for KeyParsingError {
@@ -13,14 +17,76 @@ impl Display for KeyParsingError {
 			KeyParsingError::BadSecretKey => "BadSecretKey",
 			KeyParsingError::BadPublicKey => "BadPublicKey",
 			KeyParsingError::BadKeypair => "BadKeypair",
+			KeyParsingError::BadSignature => "BadSignature",
+			KeyParsingError::UnknownAlgorithm => "UnknownAlgorithm",
+			KeyParsingError::SeedMismatch => "SeedMismatch",
 		};
 		write!(f, "{str}")
 	}
 }
 
-#[cfg(not(feature = "no_std"))]
+#[cfg(feature = "std")]
 impl std::error::Error for KeyParsingError {
 	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
 		None
 	}
 }
+
+/// Reason a key generation, signing or verification call was rejected.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Error {
+	/// The context string is longer than 255 bytes.
+	ContextTooLong,
+	/// A key, signature, seed or output buffer does not have the length the parameter set needs.
+	BadLength { expected: usize, actual: usize },
+	/// A hint count in a signature is below the previous one or above OMEGA.
+	HintCountOutOfRange,
+	/// The hint positions of a polynomial in a signature are not strictly increasing, so the
+	/// same hint could be encoded in more than one way.
+	UnsortedHint,
+	/// The unused hint positions of a signature after the last hint are not zero.
+	HintPaddingNotZero,
+	/// The response z of a signature is outside of the allowed range.
+	NormBoundExceeded,
+	/// The challenge recomputed from the message does not match the one in the signature.
+	ChallengeMismatch,
+	/// Randomness was needed but no seed was supplied and no random number generator is
+	/// available.
+	MissingRng,
+	/// The signature and the public key belong to different parameter sets.
+	AlgorithmMismatch,
+	/// Locked memory for a secret could not be allocated, e.g. because RLIMIT_MEMLOCK is too
+	/// low.
+	MemoryLockFailed,
+}
+
+impl Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Error::ContextTooLong => write!(f, "context must not be longer than 255 bytes"),
+			Error::BadLength { expected, actual } =>
+				write!(f, "bad length: expected {expected} bytes, got {actual}"),
+			Error::HintCountOutOfRange => write!(f, "hint count in signature out of range"),
+			Error::UnsortedHint => write!(f, "hint positions in signature are not sorted"),
+			Error::HintPaddingNotZero => write!(f, "hint padding in signature is not zero"),
+			Error::NormBoundExceeded => write!(f, "signature response exceeds the norm bound"),
+			Error::ChallengeMismatch => write!(f, "signature challenge does not match"),
+			Error::MissingRng =>
+				write!(f, "no seed given and no random number generator available"),
+			Error::AlgorithmMismatch => write!(f, "signature and key use different algorithms"),
+			Error::MemoryLockFailed => write!(f, "could not allocate locked memory"),
+		}
+	}
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+/// Fails with [Error::BadLength] unless `actual == expected`.
+pub(crate) fn check_len(actual: usize, expected: usize) -> Result<(), Error> {
+	if actual == expected {
+		Ok(())
+	} else {
+		Err(Error::BadLength { expected, actual })
+	}
+}
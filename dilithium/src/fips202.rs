@@ -1,5 +1,9 @@
 pub const SHAKE128_RATE: usize = 168;
 pub const SHAKE256_RATE: usize = 136;
+pub const SHA3_224_RATE: usize = 144;
+pub const SHA3_256_RATE: usize = 136;
+pub const SHA3_384_RATE: usize = 104;
+pub const SHA3_512_RATE: usize = 72;
 
 const NROUNDS: usize = 24;
 
@@ -10,6 +14,13 @@ pub struct KeccakState {
 	pub pos: usize,
 }
 
+impl zeroize::Zeroize for KeccakState {
+	fn zeroize(&mut self) {
+		self.s.zeroize();
+		self.pos.zeroize();
+	}
+}
+
 impl KeccakState {
 	/// Set the state to the initial form.
 	pub fn init(&mut self) {
@@ -472,6 +483,8 @@ pub fn shake256(output: &mut [u8], mut outlen: usize, input: &[u8], inlen: usize
 	outlen -= nblocks * SHAKE256_RATE;
 	let idx = nblocks * SHAKE256_RATE;
 	shake256_squeeze(&mut output[idx..], outlen, &mut state);
+	// The input is often a seed or key.
+	zeroize::Zeroize::zeroize(&mut state);
 }
 
 pub fn shake128_stream_init(state: &mut KeccakState, seed: &[u8], nonce: u16) {
@@ -489,3 +502,85 @@ pub fn shake256_stream_init(state: &mut KeccakState, seed: &[u8], nonce: u16) {
 	shake256_absorb(state, &t, 2);
 	shake256_finalize(state);
 }
+
+/// Keccak with rate r and padding byte p, absorbing all of input and squeezing all of output.
+fn keccak(output: &mut [u8], input: &[u8], r: usize, p: u8) {
+	let mut state = KeccakState::default();
+	keccak_absorb(&mut state, r, input, input.len());
+	keccak_finalize(&mut state.s, state.pos, r, p);
+	keccak_squeeze(output, output.len(), &mut state.s, r, r);
+	zeroize::Zeroize::zeroize(&mut state);
+}
+
+/// SHAKE128 XOF with non-incremental API, filling all of output.
+pub fn shake128(output: &mut [u8], input: &[u8]) {
+	keccak(output, input, SHAKE128_RATE, 0x1F);
+}
+
+/// SHA3-224 hash function.
+pub fn sha3_224(output: &mut [u8; 28], input: &[u8]) {
+	keccak(output, input, SHA3_224_RATE, 0x06);
+}
+
+/// SHA3-256 hash function.
+pub fn sha3_256(output: &mut [u8; 32], input: &[u8]) {
+	keccak(output, input, SHA3_256_RATE, 0x06);
+}
+
+/// SHA3-384 hash function.
+pub fn sha3_384(output: &mut [u8; 48], input: &[u8]) {
+	keccak(output, input, SHA3_384_RATE, 0x06);
+}
+
+/// SHA3-512 hash function.
+pub fn sha3_512(output: &mut [u8; 64], input: &[u8]) {
+	keccak(output, input, SHA3_512_RATE, 0x06);
+}
+
+#[cfg(test)]
+#[cfg(feature = "std")]
+mod tests {
+	use super::*;
+
+	fn hex(bytes: &[u8]) -> String {
+		bytes.iter().map(|b| format!("{b:02x}")).collect()
+	}
+
+	// Expected values from Python's hashlib; the 200-byte input spans more than one block at
+	// every rate.
+	#[test]
+	fn sha3_and_shake_match_reference() {
+		let input: Vec<u8> = (0..200u8).collect();
+
+		let mut out = [0u8; 28];
+		sha3_224(&mut out, &input);
+		assert_eq!(hex(&out), "8bcd90dbc5379549b5e78a1fbe24ae120d92caef17750461262b1e97");
+		let mut out = [0u8; 32];
+		sha3_256(&mut out, &input);
+		assert_eq!(hex(&out), "5f728f63bf5ee48c77f453c0490398fa645b8d4c4e56be9a41cfec344d6ca899");
+		sha3_256(&mut out, &[]);
+		assert_eq!(hex(&out), "a7ffc6f8bf1ed76651c14756a061d662f580ff4de43b49fa82d80a4b80f8434a");
+		let mut out = [0u8; 48];
+		sha3_384(&mut out, &input);
+		assert_eq!(
+			hex(&out),
+			"b13febb1b3c54a7c6b69367f693a1d1f3145709b6ddef23ff15874133ea1fb9cfa48ee7ff4ec9aa987dea641e33ccdf7"
+		);
+		let mut out = [0u8; 64];
+		sha3_512(&mut out, &input);
+		assert_eq!(
+			hex(&out),
+			"ea5d05f19348dd589793354793a15f37a73b4c0bb4e750b9a00757dfce2f8b65a64191bb9b137de00feef6474cfd47abf7880efbc51614a5715df12cfe0caee3"
+		);
+
+		let mut out = [0u8; 32];
+		shake128(&mut out, &input);
+		assert_eq!(hex(&out), "0c4234ca1e31801ae606f8b8d8e0665c66f42a21d601c2681858a92c79ad5d69");
+		let mut out = [0u8; 64];
+		shake256(&mut out, 64, &input, input.len());
+		assert_eq!(
+			hex(&out),
+			"4ee1ca03272b05d3bfb1e1c79a967f823b9fc5e4bb3987b1ba9e9cb5afb07a5ee3a07fbd457a94364964a841e7f466e5a022e21ab7f673c18ba98cdb1d5aecfa"
+		);
+	}
+}
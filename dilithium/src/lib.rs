@@ -1,16 +1,23 @@
-#![cfg_attr(feature = "no_std", no_std)]
+#![cfg_attr(not(feature = "std"), no_std)]
 #![allow(clippy::identity_op)]
 #![allow(clippy::needless_range_loop)]
 #![allow(clippy::precedence)]
 #![allow(clippy::unnecessary_cast)]
 #![allow(clippy::enum_variant_names)]
+#![allow(clippy::large_enum_variant)]
 
-#[cfg(feature = "no_std")]
+#[cfg(feature = "alloc")]
 extern crate alloc;
 extern crate core;
 
+pub mod algorithm;
+#[cfg(feature = "alloc")]
+pub mod batch;
 mod errors;
 pub mod fips202;
+#[cfg(all(feature = "mlock", target_os = "linux"))]
+pub mod locked;
+pub mod ml_dsa;
 pub mod ml_dsa_44;
 pub mod ml_dsa_65;
 pub mod ml_dsa_87;
@@ -19,26 +26,136 @@ pub mod packing;
 pub mod params;
 pub mod poly;
 pub mod polyvec;
+pub mod prepared;
 pub mod reduce;
 pub mod rounding;
+pub mod seed;
 pub mod sign;
+pub mod stream;
 
+pub use algorithm::{Algorithm, AnyKeypair, AnyPublicKey, AnySignature};
+#[cfg(feature = "alloc")]
+pub use batch::{verify_batch, BatchItem};
+pub use errors::{Error, KeyParsingError};
+pub use ml_dsa::{Keypair, MlDsa44, MlDsa65, MlDsa87, ParameterSet, PublicKey, SecretKey};
+pub use prepared::{PreparedPublicKey, PreparedSecretKey};
+pub use rand_core::{CryptoRng, RngCore};
+pub use seed::SeedKeypair;
+pub use stream::{Signer, Verifier};
+
+/// Hash function applied to the message by HashML-DSA (FIPS 204, section 5.4).
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum PH {
+	SHA224,
 	SHA256,
+	SHA384,
 	SHA512,
+	SHA512_224,
+	SHA512_256,
+	SHA3_224,
+	SHA3_256,
+	SHA3_384,
+	SHA3_512,
+	/// SHAKE128 with 256 bits of output.
+	SHAKE128,
+	/// SHAKE256 with 512 bits of output.
+	SHAKE256,
+}
+
+impl PH {
+	/// Every supported pre-hash function.
+	pub const ALL: [PH; 12] = [
+		PH::SHA224,
+		PH::SHA256,
+		PH::SHA384,
+		PH::SHA512,
+		PH::SHA512_224,
+		PH::SHA512_256,
+		PH::SHA3_224,
+		PH::SHA3_256,
+		PH::SHA3_384,
+		PH::SHA3_512,
+		PH::SHAKE128,
+		PH::SHAKE256,
+	];
+
+	/// Largest digest of any pre-hash function.
+	pub const MAX_DIGEST_BYTES: usize = 64;
+
+	/// DER encoding of the function's OID, which is prepended to the digest in M'.
+	pub fn oid(self) -> [u8; 11] {
+		// All are 2.16.840.1.101.3.4.2.x, under NIST's hash algorithm arc.
+		let arc = match self {
+			PH::SHA256 => 0x01,
+			PH::SHA384 => 0x02,
+			PH::SHA512 => 0x03,
+			PH::SHA224 => 0x04,
+			PH::SHA512_224 => 0x05,
+			PH::SHA512_256 => 0x06,
+			PH::SHA3_224 => 0x07,
+			PH::SHA3_256 => 0x08,
+			PH::SHA3_384 => 0x09,
+			PH::SHA3_512 => 0x0a,
+			PH::SHAKE128 => 0x0b,
+			PH::SHAKE256 => 0x0c,
+		};
+		[0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, arc]
+	}
+
+	/// Length of the digest in bytes.
+	pub fn digest_len(self) -> usize {
+		match self {
+			PH::SHA224 | PH::SHA512_224 | PH::SHA3_224 => 28,
+			PH::SHA256 | PH::SHA512_256 | PH::SHA3_256 | PH::SHAKE128 => 32,
+			PH::SHA384 | PH::SHA3_384 => 48,
+			PH::SHA512 | PH::SHA3_512 | PH::SHAKE256 => 64,
+		}
+	}
+
+	/// Hash `msg` into the start of `out`, returning the digest length.
+	pub fn digest(self, msg: &[u8], out: &mut [u8; PH::MAX_DIGEST_BYTES]) -> usize {
+		use sha2::Digest;
+		let n = self.digest_len();
+		match self {
+			PH::SHA224 => out[..n].copy_from_slice(&sha2::Sha224::digest(msg)),
+			PH::SHA256 => out[..n].copy_from_slice(&sha2::Sha256::digest(msg)),
+			PH::SHA384 => out[..n].copy_from_slice(&sha2::Sha384::digest(msg)),
+			PH::SHA512 => out[..n].copy_from_slice(&sha2::Sha512::digest(msg)),
+			PH::SHA512_224 => out[..n].copy_from_slice(&sha2::Sha512_224::digest(msg)),
+			PH::SHA512_256 => out[..n].copy_from_slice(&sha2::Sha512_256::digest(msg)),
+			PH::SHA3_224 => fips202::sha3_224((&mut out[..n]).try_into().unwrap(), msg),
+			PH::SHA3_256 => fips202::sha3_256((&mut out[..n]).try_into().unwrap(), msg),
+			PH::SHA3_384 => fips202::sha3_384((&mut out[..n]).try_into().unwrap(), msg),
+			PH::SHA3_512 => fips202::sha3_512(out, msg),
+			PH::SHAKE128 => fips202::shake128(&mut out[..n], msg),
+			PH::SHAKE256 => fips202::shake256(&mut out[..n], n, msg, msg.len()),
+		}
+		n
+	}
 }
 
-#[cfg(not(feature = "no_std"))]
-use rand::RngCore;
 /// Generate random bytes.
 ///
 /// # Arguments
 ///
 /// * 'bytes' - an array to fill with random data
 /// * 'n' - number of bytes to generate
-#[cfg(not(feature = "no_std"))]
-fn random_bytes(bytes: &mut [u8], n: usize) {
-	rand::prelude::thread_rng().try_fill_bytes(&mut bytes[..n]).unwrap();
+///
+/// Returns [Error::MissingRng] if the RNG fails or the crate is built without `getrandom`.
+#[cfg(feature = "getrandom")]
+fn random_bytes(bytes: &mut [u8], n: usize) -> Result<(), Error> {
+	rng_bytes(&mut rand_core::OsRng, &mut bytes[..n])
+}
+
+#[cfg(not(feature = "getrandom"))]
+fn random_bytes(_bytes: &mut [u8], _n: usize) -> Result<(), Error> {
+	Err(Error::MissingRng)
+}
+
+/// Fill `bytes` from a caller-supplied RNG, mapping its failure to [Error::MissingRng].
+fn rng_bytes(rng: &mut (impl RngCore + CryptoRng), bytes: &mut [u8]) -> Result<(), Error> {
+	rng.try_fill_bytes(bytes).map_err(|_| Error::MissingRng)
 }
 
 #[cfg(test)]
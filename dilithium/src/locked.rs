@@ -0,0 +1,354 @@
+//! Secret storage in locked memory (Linux, `mlock` feature).
+//!
+//! [Locked] keeps a byte array in a mapping of its own, between two inaccessible guard pages. The
+//! pages are locked into RAM with `mlock` so they are never swapped out, and marked
+//! `MADV_DONTDUMP` so they are left out of core dumps. The bytes are wiped before the mapping is
+//! released, and can only be reached from inside a closure, so no reference outlives the borrow.
+//!
+//! [LockedSecretKey] keeps an ML-DSA secret key in such storage and signs from it directly, with
+//! the low-memory signer: the key is never expanded, and the one unpacked polynomial of s1, s2 or
+//! t0 it works on at a time sits on the ordinary stack and is wiped afterwards.
+
+use core::{fmt, marker::PhantomData, ptr::NonNull};
+
+use zeroize::Zeroize;
+
+use crate::{
+	errors::{check_len, Error},
+	ml_dsa::{prehash_mu, pure_mu, ByteArray, ParameterSet, PublicKey, SecretKey, Signature},
+	params,
+	stream::secret_tr,
+	CryptoRng, RngCore, PH,
+};
+
+/// A byte array in locked, guard-paged memory that is excluded from core dumps.
+pub struct Locked<B: ByteArray> {
+	value: NonNull<B>,
+	mapping: *mut libc::c_void,
+	mapping_len: usize,
+}
+
+// The mapping is owned exclusively, the same as a Box<B>.
+unsafe impl<B: ByteArray> Send for Locked<B> {}
+unsafe impl<B: ByteArray> Sync for Locked<B> {}
+
+impl<B: ByteArray> Locked<B> {
+	/// Allocate zeroed storage.
+	///
+	/// Returns [Error::MemoryLockFailed] if the pages cannot be mapped, locked or excluded from
+	/// core dumps.
+	pub fn new() -> Result<Self, Error> {
+		let page = unsafe { libc::sysconf(libc::_SC_PAGESIZE) };
+		if page <= 0 {
+			return Err(Error::MemoryLockFailed);
+		}
+		let page = page as usize;
+		let size = core::mem::size_of::<B>();
+		let data_len = size.div_ceil(page).max(1) * page;
+		let mapping_len = data_len + 2 * page;
+
+		unsafe {
+			let mapping = libc::mmap(
+				core::ptr::null_mut(),
+				mapping_len,
+				libc::PROT_NONE,
+				libc::MAP_PRIVATE | libc::MAP_ANONYMOUS,
+				-1,
+				0,
+			);
+			if mapping == libc::MAP_FAILED {
+				return Err(Error::MemoryLockFailed);
+			}
+			// The first and last page stay PROT_NONE as guards.
+			let data = (mapping as *mut u8).add(page);
+			if libc::mprotect(data.cast(), data_len, libc::PROT_READ | libc::PROT_WRITE) != 0 ||
+				libc::mlock(data.cast(), data_len) != 0 ||
+				libc::madvise(data.cast(), data_len, libc::MADV_DONTDUMP) != 0
+			{
+				libc::munmap(mapping, mapping_len);
+				return Err(Error::MemoryLockFailed);
+			}
+
+			// Place the value against the trailing guard page so that an overrun faults.
+			let offset = (data_len - size) & !(core::mem::align_of::<B>() - 1);
+			let value = data.add(offset) as *mut B;
+			value.write(B::zeroed());
+			Ok(Locked { value: NonNull::new_unchecked(value), mapping, mapping_len })
+		}
+	}
+
+	/// Allocate storage holding a copy of `bytes`.
+	///
+	/// Returns [Error::BadLength] if `bytes` does not have the length of B.
+	pub fn from_slice(bytes: &[u8]) -> Result<Self, Error> {
+		check_len(bytes.len(), core::mem::size_of::<B>())?;
+		let mut locked = Locked::<B>::new()?;
+		locked.with_mut(|b| b.as_mut().copy_from_slice(bytes));
+		Ok(locked)
+	}
+
+	/// Borrow the bytes for the duration of `f`.
+	pub fn with<R>(&self, f: impl FnOnce(&B) -> R) -> R {
+		f(unsafe { self.value.as_ref() })
+	}
+
+	/// Mutably borrow the bytes for the duration of `f`.
+	pub fn with_mut<R>(&mut self, f: impl FnOnce(&mut B) -> R) -> R {
+		f(unsafe { self.value.as_mut() })
+	}
+}
+
+impl<B: ByteArray> Drop for Locked<B> {
+	fn drop(&mut self) {
+		unsafe {
+			self.value.as_mut().zeroize();
+			// Unmapping also unlocks the pages.
+			libc::munmap(self.mapping, self.mapping_len);
+		}
+	}
+}
+
+impl<B: ByteArray> fmt::Debug for Locked<B> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Locked").finish_non_exhaustive()
+	}
+}
+
+/// An ML-DSA secret key kept in [Locked] storage.
+pub struct LockedSecretKey<P: ParameterSet> {
+	bytes: Locked<P::SecretKeyBytes>,
+	_marker: PhantomData<P>,
+}
+
+impl<P: ParameterSet> LockedSecretKey<P> {
+	/// Move `secret` into locked storage; the original is wiped when it is dropped here.
+	pub fn new(secret: SecretKey<P>) -> Result<Self, Error> {
+		LockedSecretKey::from_bytes(secret.bytes.as_ref())
+	}
+
+	/// Copy an encoded secret key into locked storage.
+	pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+		Ok(LockedSecretKey { bytes: Locked::from_slice(bytes)?, _marker: PhantomData })
+	}
+
+	/// Generate a keypair whose secret key is written straight into locked storage; see
+	/// [crate::ml_dsa::Keypair::generate].
+	pub fn generate(entropy: Option<&[u8]>) -> Result<(Self, PublicKey<P>), Error> {
+		Self::expand(entropy, true)
+	}
+
+	/// As [LockedSecretKey::generate], with the seed drawn from `rng`.
+	pub fn generate_with_rng(
+		rng: &mut (impl RngCore + CryptoRng),
+	) -> Result<(Self, PublicKey<P>), Error> {
+		let mut seed = [0u8; params::SEEDBYTES];
+		crate::rng_bytes(rng, &mut seed)?;
+		let generated = Self::generate(Some(&seed));
+		seed.zeroize();
+		generated
+	}
+
+	/// As [LockedSecretKey::generate], with the pre-standard key expansion; see
+	/// [crate::ml_dsa::Keypair::generate_legacy].
+	pub fn generate_legacy(entropy: Option<&[u8]>) -> Result<(Self, PublicKey<P>), Error> {
+		Self::expand(entropy, false)
+	}
+
+	fn expand(
+		entropy: Option<&[u8]>,
+		domain_separated: bool,
+	) -> Result<(Self, PublicKey<P>), Error> {
+		let mut bytes = Locked::<P::SecretKeyBytes>::new()?;
+		let mut pk = P::PublicKeyBytes::zeroed();
+		bytes.with_mut(|sk| {
+			crate::sign::generate::<P>(pk.as_mut(), sk.as_mut(), entropy, domain_separated)
+		})?;
+		Ok((LockedSecretKey { bytes, _marker: PhantomData }, PublicKey { bytes: pk }))
+	}
+
+	/// Borrow the encoded secret key for the duration of `f`.
+	pub fn with_bytes<R>(&self, f: impl FnOnce(&P::SecretKeyBytes) -> R) -> R {
+		self.bytes.with(f)
+	}
+
+	/// Compute a signature for a given message; see [SecretKey::sign].
+	pub fn sign(
+		&self,
+		msg: &[u8],
+		ctx: Option<&[u8]>,
+		hedged: bool,
+	) -> Result<Signature<P>, Error> {
+		self.sign_hedged(hedged, |tr| pure_mu(tr, ctx, msg))
+	}
+
+	/// Compute a hedged signature with randomness from `rng`; see [SecretKey::sign_with_rng].
+	pub fn sign_with_rng(
+		&self,
+		msg: &[u8],
+		ctx: Option<&[u8]>,
+		rng: &mut (impl RngCore + CryptoRng),
+	) -> Result<Signature<P>, Error> {
+		let mut rnd = [0u8; params::SEEDBYTES];
+		crate::rng_bytes(rng, &mut rnd)?;
+		self.sign_low_memory(&mut rnd, |tr| pure_mu(tr, ctx, msg))
+	}
+
+	/// Compute a HashML-DSA signature for a given message; see [SecretKey::prehash_sign].
+	pub fn prehash_sign(
+		&self,
+		msg: &[u8],
+		ctx: Option<&[u8]>,
+		hedged: bool,
+		ph: PH,
+	) -> Result<Signature<P>, Error> {
+		let mut digest = [0u8; PH::MAX_DIGEST_BYTES];
+		let n = ph.digest(msg, &mut digest);
+		self.sign_prehashed(&digest[..n], ctx, hedged, ph)
+	}
+
+	/// Compute a HashML-DSA signature over a digest; see [SecretKey::sign_prehashed].
+	pub fn sign_prehashed(
+		&self,
+		digest: &[u8],
+		ctx: Option<&[u8]>,
+		hedged: bool,
+		ph: PH,
+	) -> Result<Signature<P>, Error> {
+		self.sign_hedged(hedged, |tr| prehash_mu(tr, ctx, digest, ph))
+	}
+
+	/// Compute a signature from a message representative; see [SecretKey::sign_mu].
+	pub fn sign_mu(&self, mu: &[u8], hedged: bool) -> Result<Signature<P>, Error> {
+		let mu = crate::sign::as_mu(mu)?;
+		self.sign_hedged(hedged, |_| Ok(*mu))
+	}
+
+	/// Sign the mu that `mu` computes from tr, with fresh randomness if `hedged`.
+	fn sign_hedged(
+		&self,
+		hedged: bool,
+		mu: impl FnOnce(&[u8]) -> Result<[u8; params::CRHBYTES], Error>,
+	) -> Result<Signature<P>, Error> {
+		let mut rnd = [0u8; params::SEEDBYTES];
+		if hedged {
+			crate::random_bytes(&mut rnd, params::SEEDBYTES)?;
+		}
+		self.sign_low_memory(&mut rnd, mu)
+	}
+
+	/// Sign from the locked key without expanding it, then wipe `rnd`.
+	fn sign_low_memory(
+		&self,
+		rnd: &mut [u8; params::SEEDBYTES],
+		mu: impl FnOnce(&[u8]) -> Result<[u8; params::CRHBYTES], Error>,
+	) -> Result<Signature<P>, Error> {
+		let result = self.bytes.with(|sk| {
+			let sk = sk.as_ref();
+			let mut mu = mu(secret_tr(sk))?;
+			let mut sig = P::SignatureBytes::zeroed();
+			P::sign_low_memory_mu(sk, sig.as_mut(), &mu, rnd);
+			mu.zeroize();
+			Ok(sig)
+		});
+		rnd.zeroize();
+		result
+	}
+}
+
+impl<P: ParameterSet> fmt::Debug for LockedSecretKey<P> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("LockedSecretKey")
+			.field("algorithm", &P::ALGORITHM)
+			.finish_non_exhaustive()
+	}
+}
+
+#[cfg(test)]
+#[cfg(feature = "std")]
+mod tests {
+	use super::*;
+	use crate::ml_dsa::{Keypair, MlDsa44, MlDsa87};
+
+	/// The VmFlags line of the mapping in /proc/self/smaps that contains `addr`.
+	fn vm_flags(addr: usize) -> String {
+		let smaps = std::fs::read_to_string("/proc/self/smaps").unwrap();
+		let mut inside = false;
+		for line in smaps.lines() {
+			if let Some((range, _)) = line.split_once(' ') {
+				if let Some((start, end)) = range.split_once('-') {
+					if let (Ok(start), Ok(end)) =
+						(usize::from_str_radix(start, 16), usize::from_str_radix(end, 16))
+					{
+						inside = start <= addr && addr < end;
+						continue;
+					}
+				}
+			}
+			if inside && line.starts_with("VmFlags:") {
+				return line.to_string();
+			}
+		}
+		panic!("no mapping contains {addr:#x}");
+	}
+
+	#[test]
+	fn pages_are_locked_and_not_dumped() {
+		let locked = Locked::<[u8; 64]>::from_slice(&[7u8; 64]).unwrap();
+		assert!(locked.with(|b| b.iter().all(|&x| x == 7)));
+
+		let flags = vm_flags(locked.value.as_ptr() as usize);
+		assert!(flags.split_whitespace().any(|f| f == "lo"), "{flags}");
+		assert!(flags.split_whitespace().any(|f| f == "dd"), "{flags}");
+
+		// The pages either side are guards.
+		let page = unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as usize;
+		let before = vm_flags(locked.mapping as usize);
+		assert!(!before.split_whitespace().any(|f| f == "rd"), "{before}");
+		let after = vm_flags(locked.mapping as usize + locked.mapping_len - page);
+		assert!(!after.split_whitespace().any(|f| f == "rd"), "{after}");
+	}
+
+	#[test]
+	fn signs_like_secret_key() {
+		let seed = [5u8; crate::params::SEEDBYTES];
+		let keys = Keypair::<MlDsa87>::generate(Some(&seed)).unwrap();
+		let (locked, public) = LockedSecretKey::<MlDsa87>::generate(Some(&seed)).unwrap();
+		assert_eq!(public, keys.public);
+		locked.with_bytes(|sk| assert_eq!(sk, &keys.secret.bytes));
+
+		let sig = locked.sign(b"msg", Some(b"ctx"), false).unwrap();
+		assert_eq!(sig, keys.sign(b"msg", Some(b"ctx"), false).unwrap());
+		assert!(public.verify(b"msg", &sig, Some(b"ctx")).is_ok());
+
+		let mu = public.message_representative(b"msg", Some(b"ctx")).unwrap();
+		assert_eq!(locked.sign_mu(&mu, false).unwrap(), sig);
+
+		let sig = locked.prehash_sign(b"msg", None, true, crate::PH::SHA512).unwrap();
+		assert!(public.prehash_verify(b"msg", &sig, None, crate::PH::SHA512).is_ok());
+
+		let moved = LockedSecretKey::new(keys.secret.clone()).unwrap();
+		assert_eq!(moved.sign(b"msg", None, false), keys.sign(b"msg", None, false));
+		assert_eq!(format!("{moved:?}"), "LockedSecretKey { algorithm: MlDsa87, .. }");
+
+		use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+		let (locked, public) =
+			LockedSecretKey::<MlDsa87>::generate_with_rng(&mut ChaCha20Rng::seed_from_u64(1))
+				.unwrap();
+		let keys =
+			Keypair::<MlDsa87>::generate_with_rng(&mut ChaCha20Rng::seed_from_u64(1)).unwrap();
+		assert_eq!(public, keys.public);
+		let mut rng = ChaCha20Rng::seed_from_u64(2);
+		assert_eq!(
+			locked.sign_with_rng(b"msg", None, &mut rng.clone()).unwrap(),
+			keys.sign_with_rng(b"msg", None, &mut rng).unwrap()
+		);
+	}
+
+	#[test]
+	fn rejects_bad_lengths() {
+		assert_eq!(
+			LockedSecretKey::<MlDsa44>::from_bytes(&[0u8; 10]).map(|_| ()),
+			Err(Error::BadLength { expected: MlDsa44::SECRETKEYBYTES, actual: 10 })
+		);
+	}
+}
@@ -0,0 +1,1458 @@
+//! ML-DSA (FIPS 204) keys and signatures, generic over the parameter set.
+//!
+//! [Keypair], [SecretKey] and [PublicKey] take a [ParameterSet] type parameter ([MlDsa44],
+//! [MlDsa65] or [MlDsa87]) so that code can be written once for every security level. The
+//! `ml_dsa_44`, `ml_dsa_65` and `ml_dsa_87` modules provide aliases fixed to one level.
+
+use core::{fmt, hash::Hash};
+
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use crate::{
+	algorithm::Algorithm,
+	errors::{check_len, Error, KeyParsingError},
+	fips202,
+	prepared::{PreparedPublicKey, PreparedSecretKey},
+	stream::{finish_mu, public_tr, secret_tr, start_mu, Signer, Verifier},
+	CryptoRng, RngCore, PH,
+};
+
+/// Fixed size byte encoding of a key or signature; implemented for `[u8; N]`.
+pub trait ByteArray:
+	AsRef<[u8]> + AsMut<[u8]> + Copy + Eq + Ord + Hash + fmt::Debug + Zeroize + Send + Sync + 'static
+{
+	/// Returns an all-zero array.
+	fn zeroed() -> Self;
+
+	/// Copy `bytes` into a new array, or None if the length does not match.
+	fn try_from_slice(bytes: &[u8]) -> Option<Self>;
+}
+
+impl<const N: usize> ByteArray for [u8; N] {
+	fn zeroed() -> Self {
+		[0u8; N]
+	}
+
+	fn try_from_slice(bytes: &[u8]) -> Option<Self> {
+		bytes.try_into().ok()
+	}
+}
+
+/// An ML-DSA parameter set. Implemented by [MlDsa44], [MlDsa65] and [MlDsa87] only.
+pub trait ParameterSet:
+	sealed::Sealed + Copy + Default + fmt::Debug + Eq + Ord + Hash + Send + Sync + 'static
+{
+	/// Runtime identifier of this parameter set.
+	const ALGORITHM: Algorithm;
+	/// Rows in A.
+	const K: usize;
+	/// Columns in A.
+	const L: usize;
+	const ETA: usize;
+	/// Number of +-1s in c.
+	const TAU: usize;
+	const BETA: usize;
+	/// y coefficient range.
+	const GAMMA1: usize;
+	/// Low-order rounding range.
+	const GAMMA2: usize;
+	/// Maximum number of hint bits.
+	const OMEGA: usize;
+	const C_DASH_BYTES: usize;
+	const POLYZ_PACKEDBYTES: usize;
+	const POLYW1_PACKEDBYTES: usize;
+	const POLYETA_PACKEDBYTES: usize;
+	const PUBLICKEYBYTES: usize;
+	const SECRETKEYBYTES: usize;
+	const SIGNBYTES: usize;
+	const KEYPAIRBYTES: usize;
+
+	/// `[u8; PUBLICKEYBYTES]`
+	type PublicKeyBytes: ByteArray;
+	/// `[u8; SECRETKEYBYTES]`
+	type SecretKeyBytes: ByteArray;
+	/// `[u8; SIGNBYTES]`
+	type SignatureBytes: ByteArray;
+	/// `[u8; KEYPAIRBYTES]`
+	type KeypairBytes: ByteArray;
+}
+
+pub(crate) mod sealed {
+	use zeroize::Zeroize;
+
+	use crate::{params, poly::Poly, polyvec::Polyvec};
+
+	/// A public key expanded for verification: the matrix A and t1 * 2^d, both in the NTT
+	/// domain, and tr = H(pk).
+	#[derive(Clone)]
+	pub struct PublicKeyState<const K: usize, const L: usize> {
+		pub(crate) mat: [Polyvec<L>; K],
+		pub(crate) t1: Polyvec<K>,
+		pub(crate) tr: [u8; params::TR_BYTES],
+	}
+
+	/// A secret key expanded for signing: the matrix A and s1, s2 and t0 in the NTT domain,
+	/// together with K and tr. Everything but A is overwritten with zeroes when it is dropped.
+	#[derive(Clone)]
+	pub struct SecretKeyState<const K: usize, const L: usize> {
+		pub(crate) mat: [Polyvec<L>; K],
+		pub(crate) s1: Polyvec<L>,
+		pub(crate) s2: Polyvec<K>,
+		pub(crate) t0: Polyvec<K>,
+		pub(crate) key: [u8; params::SEEDBYTES],
+		pub(crate) tr: [u8; params::TR_BYTES],
+	}
+
+	impl<const K: usize, const L: usize> Zeroize for SecretKeyState<K, L> {
+		fn zeroize(&mut self) {
+			self.s1.zeroize();
+			self.s2.zeroize();
+			self.t0.zeroize();
+			self.key.zeroize();
+			self.tr.zeroize();
+		}
+	}
+
+	impl<const K: usize, const L: usize> Drop for SecretKeyState<K, L> {
+		fn drop(&mut self) {
+			self.zeroize();
+		}
+	}
+
+	/// Level specific polynomial routines and the K, L instantiations of the signing code. Kept
+	/// out of [super::ParameterSet] so that it cannot be implemented outside this crate.
+	pub trait Sealed {
+		/// `PublicKeyState<K, L>`
+		type PublicKeyState: Clone + Send + Sync + 'static;
+		/// `SecretKeyState<K, L>`
+		type SecretKeyState: Clone + Zeroize + Send + Sync + 'static;
+
+		fn uniform_eta(a: &mut Poly, seed: &[u8], nonce: u16);
+		fn uniform_gamma1(a: &mut Poly, seed: &[u8], nonce: u16);
+		fn challenge(c: &mut Poly, seed: &[u8]);
+		fn eta_pack(r: &mut [u8], a: &Poly);
+		fn eta_unpack(r: &mut Poly, a: &[u8]);
+		fn z_pack(r: &mut [u8], a: &Poly);
+		fn z_unpack(r: &mut Poly, a: &[u8]);
+		fn w1_pack(r: &mut [u8], a: &Poly);
+		fn decompose(a1: &mut Poly, a0: &mut Poly);
+		fn make_hint(h: &mut Poly, a0: &Poly, a1: &Poly) -> i32;
+		fn use_hint(a: &mut Poly, hint: &Poly);
+
+		fn expand_keypair(pk: &mut [u8], sk: &mut [u8], seed: &[u8], fips204: bool);
+		fn expand_secret_key(sk: &[u8]) -> Self::SecretKeyState;
+		fn public_from_secret(pk: &mut [u8], sk: &[u8]) -> bool;
+		fn sign_expanded(sk: &Self::SecretKeyState, sig: &mut [u8], msg: &[u8], rnd: &[u8]);
+		fn sign_expanded_mu(
+			sk: &Self::SecretKeyState,
+			sig: &mut [u8],
+			mu: &[u8; crate::params::CRHBYTES],
+			rnd: &[u8],
+		);
+		fn secret_tr(sk: &Self::SecretKeyState) -> &[u8; crate::params::TR_BYTES];
+		fn sign_low_memory_mu(
+			sk: &[u8],
+			sig: &mut [u8],
+			mu: &[u8; crate::params::CRHBYTES],
+			rnd: &[u8],
+		);
+		fn prepare_public_key(pk: &[u8]) -> Self::PublicKeyState;
+		fn expand_public_key(pk: &[u8], tr: &[u8; crate::params::TR_BYTES])
+			-> Self::PublicKeyState;
+		fn check_signature_encoding(sig: &[u8]) -> Result<(), crate::Error>;
+		fn public_tr(pk: &Self::PublicKeyState) -> &[u8; crate::params::TR_BYTES];
+		fn verify_prepared(
+			pk: &Self::PublicKeyState,
+			sig: &[u8],
+			m: &[u8],
+		) -> Result<(), crate::Error>;
+		fn verify_prepared_mu(
+			pk: &Self::PublicKeyState,
+			sig: &[u8],
+			mu: &[u8; crate::params::CRHBYTES],
+		) -> Result<(), crate::Error>;
+	}
+}
+
+macro_rules! parameter_set {
+	($(#[$meta:meta])* $name:ident, $level:ident) => {
+		$(#[$meta])*
+		#[derive(Clone, Copy, Default, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+		pub struct $name;
+
+		impl ParameterSet for $name {
+			const ALGORITHM: Algorithm = Algorithm::$name;
+			const K: usize = crate::params::$level::K;
+			const L: usize = crate::params::$level::L;
+			const ETA: usize = crate::params::$level::ETA;
+			const TAU: usize = crate::params::$level::TAU;
+			const BETA: usize = crate::params::$level::BETA;
+			const GAMMA1: usize = crate::params::$level::GAMMA1;
+			const GAMMA2: usize = crate::params::$level::GAMMA2;
+			const OMEGA: usize = crate::params::$level::OMEGA;
+			const C_DASH_BYTES: usize = crate::params::$level::C_DASH_BYTES;
+			const POLYZ_PACKEDBYTES: usize = crate::params::$level::POLYZ_PACKEDBYTES;
+			const POLYW1_PACKEDBYTES: usize = crate::params::$level::POLYW1_PACKEDBYTES;
+			const POLYETA_PACKEDBYTES: usize = crate::params::$level::POLYETA_PACKEDBYTES;
+			const PUBLICKEYBYTES: usize = crate::params::$level::PUBLICKEYBYTES;
+			const SECRETKEYBYTES: usize = crate::params::$level::SECRETKEYBYTES;
+			const SIGNBYTES: usize = crate::params::$level::SIGNBYTES;
+			const KEYPAIRBYTES: usize = Self::SECRETKEYBYTES + Self::PUBLICKEYBYTES;
+
+			type PublicKeyBytes = [u8; crate::params::$level::PUBLICKEYBYTES];
+			type SecretKeyBytes = [u8; crate::params::$level::SECRETKEYBYTES];
+			type SignatureBytes = [u8; crate::params::$level::SIGNBYTES];
+			type KeypairBytes = [u8; crate::params::$level::SECRETKEYBYTES +
+				crate::params::$level::PUBLICKEYBYTES];
+		}
+
+		// The generic signing code sizes its buffers by these bounds.
+		const _: () = assert!(
+			crate::params::$level::C_DASH_BYTES <= crate::params::C_DASH_BYTES_MAX &&
+				crate::params::$level::K * crate::params::$level::POLYW1_PACKEDBYTES <=
+					crate::params::POLYVECK_W1_PACKEDBYTES_MAX
+		);
+
+		impl sealed::Sealed for $name {
+			type PublicKeyState = sealed::PublicKeyState<
+				{ crate::params::$level::K },
+				{ crate::params::$level::L },
+			>;
+			type SecretKeyState = sealed::SecretKeyState<
+				{ crate::params::$level::K },
+				{ crate::params::$level::L },
+			>;
+
+			fn uniform_eta(a: &mut crate::poly::Poly, seed: &[u8], nonce: u16) {
+				crate::poly::$level::uniform_eta(a, seed, nonce)
+			}
+			fn uniform_gamma1(a: &mut crate::poly::Poly, seed: &[u8], nonce: u16) {
+				crate::poly::$level::uniform_gamma1(a, seed, nonce)
+			}
+			fn challenge(c: &mut crate::poly::Poly, seed: &[u8]) {
+				crate::poly::$level::challenge(c, seed)
+			}
+			fn eta_pack(r: &mut [u8], a: &crate::poly::Poly) {
+				crate::poly::$level::eta_pack(r, a)
+			}
+			fn eta_unpack(r: &mut crate::poly::Poly, a: &[u8]) {
+				crate::poly::$level::eta_unpack(r, a)
+			}
+			fn z_pack(r: &mut [u8], a: &crate::poly::Poly) {
+				crate::poly::$level::z_pack(r, a)
+			}
+			fn z_unpack(r: &mut crate::poly::Poly, a: &[u8]) {
+				crate::poly::$level::z_unpack(r, a)
+			}
+			fn w1_pack(r: &mut [u8], a: &crate::poly::Poly) {
+				crate::poly::$level::w1_pack(r, a)
+			}
+			fn decompose(a1: &mut crate::poly::Poly, a0: &mut crate::poly::Poly) {
+				crate::poly::$level::decompose(a1, a0)
+			}
+			fn make_hint(
+				h: &mut crate::poly::Poly,
+				a0: &crate::poly::Poly,
+				a1: &crate::poly::Poly,
+			) -> i32 {
+				crate::poly::$level::make_hint(h, a0, a1)
+			}
+			fn use_hint(a: &mut crate::poly::Poly, hint: &crate::poly::Poly) {
+				crate::poly::$level::use_hint(a, hint)
+			}
+
+			fn expand_keypair(pk: &mut [u8], sk: &mut [u8], seed: &[u8], fips204: bool) {
+				crate::sign::expand_keypair::<
+					Self,
+					{ crate::params::$level::K },
+					{ crate::params::$level::L },
+				>(pk, sk, seed, fips204)
+			}
+			fn expand_secret_key(sk: &[u8]) -> Self::SecretKeyState {
+				crate::sign::expand_secret_key::<
+					Self,
+					{ crate::params::$level::K },
+					{ crate::params::$level::L },
+				>(sk)
+			}
+			fn public_from_secret(pk: &mut [u8], sk: &[u8]) -> bool {
+				crate::sign::public_from_secret::<
+					Self,
+					{ crate::params::$level::K },
+					{ crate::params::$level::L },
+				>(pk, sk)
+			}
+			fn sign_expanded(
+				sk: &Self::SecretKeyState,
+				sig: &mut [u8],
+				msg: &[u8],
+				rnd: &[u8],
+			) {
+				crate::sign::sign_expanded::<
+					Self,
+					{ crate::params::$level::K },
+					{ crate::params::$level::L },
+				>(sk, sig, msg, rnd)
+			}
+			fn sign_expanded_mu(
+				sk: &Self::SecretKeyState,
+				sig: &mut [u8],
+				mu: &[u8; crate::params::CRHBYTES],
+				rnd: &[u8],
+			) {
+				crate::sign::sign_expanded_mu::<
+					Self,
+					{ crate::params::$level::K },
+					{ crate::params::$level::L },
+				>(sk, sig, mu, rnd)
+			}
+			fn secret_tr(sk: &Self::SecretKeyState) -> &[u8; crate::params::TR_BYTES] {
+				&sk.tr
+			}
+			fn sign_low_memory_mu(
+				sk: &[u8],
+				sig: &mut [u8],
+				mu: &[u8; crate::params::CRHBYTES],
+				rnd: &[u8],
+			) {
+				crate::sign::sign_low_memory_mu::<
+					Self,
+					{ crate::params::$level::K },
+					{ crate::params::$level::L },
+				>(sk, sig, mu, rnd)
+			}
+			fn prepare_public_key(pk: &[u8]) -> Self::PublicKeyState {
+				crate::sign::prepare_public_key::<
+					Self,
+					{ crate::params::$level::K },
+					{ crate::params::$level::L },
+				>(pk)
+			}
+			fn expand_public_key(
+				pk: &[u8],
+				tr: &[u8; crate::params::TR_BYTES],
+			) -> Self::PublicKeyState {
+				crate::sign::expand_public_key::<
+					{ crate::params::$level::K },
+					{ crate::params::$level::L },
+				>(pk, tr)
+			}
+			fn public_tr(pk: &Self::PublicKeyState) -> &[u8; crate::params::TR_BYTES] {
+				&pk.tr
+			}
+			fn check_signature_encoding(sig: &[u8]) -> Result<(), crate::Error> {
+				crate::sign::check_signature_encoding::<
+					Self,
+					{ crate::params::$level::K },
+					{ crate::params::$level::L },
+				>(sig)
+			}
+			fn verify_prepared(
+				pk: &Self::PublicKeyState,
+				sig: &[u8],
+				m: &[u8],
+			) -> Result<(), crate::Error> {
+				crate::sign::verify_prepared::<
+					Self,
+					{ crate::params::$level::K },
+					{ crate::params::$level::L },
+				>(pk, sig, m)
+			}
+			fn verify_prepared_mu(
+				pk: &Self::PublicKeyState,
+				sig: &[u8],
+				mu: &[u8; crate::params::CRHBYTES],
+			) -> Result<(), crate::Error> {
+				crate::sign::verify_prepared_mu::<
+					Self,
+					{ crate::params::$level::K },
+					{ crate::params::$level::L },
+				>(pk, sig, mu)
+			}
+		}
+	};
+}
+
+parameter_set!(
+	/// ML-DSA-44, NIST security category 2.
+	MlDsa44,
+	ml_dsa_44
+);
+parameter_set!(
+	/// ML-DSA-65, NIST security category 3.
+	MlDsa65,
+	ml_dsa_65
+);
+parameter_set!(
+	/// ML-DSA-87, NIST security category 5.
+	MlDsa87,
+	ml_dsa_87
+);
+
+/// Signature bytes of parameter set P.
+pub type Signature<P> = <P as ParameterSet>::SignatureBytes;
+
+/// A pair of private and public keys.
+#[derive(Clone)]
+pub struct Keypair<P: ParameterSet> {
+	pub secret: SecretKey<P>,
+	pub public: PublicKey<P>,
+}
+
+impl<P: ParameterSet> Keypair<P> {
+	/// Generate a Keypair instance.
+	///
+	/// # Arguments
+	///
+	/// * 'entropy' - optional bytes for determining the generation process
+	///
+	/// Returns an instance of Keypair, or [Error::MissingRng] if no entropy is given and no RNG is
+	/// available
+	pub fn generate(entropy: Option<&[u8]>) -> Result<Keypair<P>, Error> {
+		let mut pk = P::PublicKeyBytes::zeroed();
+		let mut sk = P::SecretKeyBytes::zeroed();
+		crate::sign::keypair::<P>(pk.as_mut(), sk.as_mut(), entropy)?;
+		Ok(Keypair { secret: SecretKey { bytes: sk }, public: PublicKey { bytes: pk } })
+	}
+
+	/// Run ML-DSA.KeyGen_internal on a params::SEEDBYTES seed ξ; the same as
+	/// [Keypair::generate] with that seed. Keeping just the seed is enough to restore the keys;
+	/// see [crate::seed::SeedKeypair].
+	pub fn from_seed(seed: &[u8; crate::params::SEEDBYTES]) -> Keypair<P> {
+		let mut pk = P::PublicKeyBytes::zeroed();
+		let mut sk = P::SecretKeyBytes::zeroed();
+		P::expand_keypair(pk.as_mut(), sk.as_mut(), seed, true);
+		Keypair { secret: SecretKey { bytes: sk }, public: PublicKey { bytes: pk } }
+	}
+
+	/// Generate a Keypair from a seed drawn from `rng`.
+	///
+	/// Returns an instance of Keypair, or [Error::MissingRng] if 'rng' fails
+	pub fn generate_with_rng(rng: &mut (impl RngCore + CryptoRng)) -> Result<Keypair<P>, Error> {
+		let mut seed = [0u8; crate::params::SEEDBYTES];
+		crate::rng_bytes(rng, &mut seed)?;
+		let keys = Keypair::generate(Some(&seed));
+		seed.zeroize();
+		keys
+	}
+
+	/// Generate a Keypair instance using the pre-standard (draft) key expansion.
+	///
+	/// Use this only to regenerate keys that were derived from a seed before the FIPS 204 key
+	/// generation was adopted; see [crate::sign::keypair_legacy].
+	///
+	/// # Arguments
+	///
+	/// * 'entropy' - optional bytes for determining the generation process
+	///
+	/// Returns an instance of Keypair
+	pub fn generate_legacy(entropy: Option<&[u8]>) -> Result<Keypair<P>, Error> {
+		let mut pk = P::PublicKeyBytes::zeroed();
+		let mut sk = P::SecretKeyBytes::zeroed();
+		crate::sign::keypair_legacy::<P>(pk.as_mut(), sk.as_mut(), entropy)?;
+		Ok(Keypair { secret: SecretKey { bytes: sk }, public: PublicKey { bytes: pk } })
+	}
+
+	/// Convert a Keypair to a bytes array.
+	///
+	/// Returns an array containing private and public keys bytes. Unlike the Keypair itself the
+	/// copy is not wiped on drop.
+	pub fn to_bytes(&self) -> P::KeypairBytes {
+		let mut result = P::KeypairBytes::zeroed();
+		result.as_mut()[..P::SECRETKEYBYTES].copy_from_slice(self.secret.bytes.as_ref());
+		result.as_mut()[P::SECRETKEYBYTES..].copy_from_slice(self.public.bytes.as_ref());
+		result
+	}
+
+	/// Create a Keypair from bytes.
+	///
+	/// # Arguments
+	///
+	/// * 'bytes' - private and public keys bytes
+	///
+	/// Returns a Keypair
+	pub fn from_bytes(bytes: &[u8]) -> Result<Keypair<P>, KeyParsingError> {
+		if bytes.len() != P::KEYPAIRBYTES {
+			return Err(KeyParsingError::BadKeypair);
+		}
+		let (secret_bytes, public_bytes) = bytes.split_at(P::SECRETKEYBYTES);
+		let secret =
+			SecretKey::from_bytes(secret_bytes).map_err(|_| KeyParsingError::BadKeypair)?;
+		let public =
+			PublicKey::from_bytes(public_bytes).map_err(|_| KeyParsingError::BadKeypair)?;
+		Ok(Keypair { secret, public })
+	}
+
+	/// Create a Keypair from bytes like [Keypair::from_bytes], and also check that the halves
+	/// belong together, so that a corrupted keystore is caught when it is loaded rather than by
+	/// the signatures failing to verify. This costs about as much as a key generation.
+	///
+	/// # Arguments
+	///
+	/// * 'bytes' - private and public keys bytes
+	///
+	/// Returns a Keypair, [KeyParsingError::BadSecretKey] if the secret key fails the checks of
+	/// [SecretKey::public_key], or [KeyParsingError::BadKeypair] for a wrong length or a public
+	/// key that differs from the one the secret key gives
+	pub fn from_bytes_checked(bytes: &[u8]) -> Result<Keypair<P>, KeyParsingError> {
+		let keys = Keypair::from_bytes(bytes)?;
+		if keys.secret.public_key()? != keys.public {
+			return Err(KeyParsingError::BadKeypair);
+		}
+		Ok(keys)
+	}
+
+	/// Compute a signature for a given message.
+	///
+	/// # Arguments
+	///
+	/// * 'msg' - message to sign
+	/// * 'ctx' - context string
+	/// * 'hedged' - wether to use RNG or not
+	///
+	/// Returns Signature
+	pub fn sign(
+		&self,
+		msg: &[u8],
+		ctx: Option<&[u8]>,
+		hedged: bool,
+	) -> Result<Signature<P>, Error> {
+		self.secret.sign(msg, ctx, hedged)
+	}
+
+	/// Compute a hedged signature with randomness from `rng`; see [SecretKey::sign_with_rng].
+	pub fn sign_with_rng(
+		&self,
+		msg: &[u8],
+		ctx: Option<&[u8]>,
+		rng: &mut (impl RngCore + CryptoRng),
+	) -> Result<Signature<P>, Error> {
+		self.secret.sign_with_rng(msg, ctx, rng)
+	}
+
+	/// Compute a signature with a fraction of the stack; see [SecretKey::sign_low_memory].
+	pub fn sign_low_memory(
+		&self,
+		msg: &[u8],
+		ctx: Option<&[u8]>,
+		hedged: bool,
+	) -> Result<Signature<P>, Error> {
+		self.secret.sign_low_memory(msg, ctx, hedged)
+	}
+
+	/// Verify a signature for a given message with a public key.
+	///
+	/// # Arguments
+	///
+	/// * 'msg' - message that is claimed to be signed
+	/// * 'sig' - signature to verify
+	/// * 'ctx' - context string
+	///
+	/// Returns Ok(()) if the verification process was successful, otherwise the reason it failed
+	pub fn verify(&self, msg: &[u8], sig: &[u8], ctx: Option<&[u8]>) -> Result<(), Error> {
+		self.public.verify(msg, sig, ctx)
+	}
+
+	/// Compute a HashML-DSA signature for a given message.
+	///
+	/// # Arguments
+	///
+	/// * 'msg' - message to sign
+	/// * 'ctx' - context string
+	/// * 'hedged' - wether to use RNG or not
+	/// * 'ph' - pre-hash function
+	///
+	/// Returns Signature
+	pub fn prehash_sign(
+		&self,
+		msg: &[u8],
+		ctx: Option<&[u8]>,
+		hedged: bool,
+		ph: PH,
+	) -> Result<Signature<P>, Error> {
+		self.secret.prehash_sign(msg, ctx, hedged, ph)
+	}
+
+	/// Compute a HashML-DSA signature over a digest computed elsewhere; see
+	/// [SecretKey::sign_prehashed].
+	pub fn sign_prehashed(
+		&self,
+		digest: &[u8],
+		ctx: Option<&[u8]>,
+		hedged: bool,
+		ph: PH,
+	) -> Result<Signature<P>, Error> {
+		self.secret.sign_prehashed(digest, ctx, hedged, ph)
+	}
+
+	/// Verify a HashML-DSA signature for a given message with a public key.
+	///
+	/// # Arguments
+	///
+	/// * 'msg' - message that is claimed to be signed
+	/// * 'sig' - signature to verify
+	/// * 'ctx' - context string
+	/// * 'ph' - pre-hash function
+	///
+	/// Returns Ok(()) if the verification process was successful, otherwise the reason it failed
+	pub fn prehash_verify(
+		&self,
+		msg: &[u8],
+		sig: &[u8],
+		ctx: Option<&[u8]>,
+		ph: PH,
+	) -> Result<(), Error> {
+		self.public.prehash_verify(msg, sig, ctx, ph)
+	}
+
+	/// Verify a HashML-DSA signature over a digest computed elsewhere; see
+	/// [PublicKey::verify_prehashed].
+	pub fn verify_prehashed(
+		&self,
+		digest: &[u8],
+		sig: &[u8],
+		ctx: Option<&[u8]>,
+		ph: PH,
+	) -> Result<(), Error> {
+		self.public.verify_prehashed(digest, sig, ctx, ph)
+	}
+
+	/// Compute a signature from a message representative; see [SecretKey::sign_mu].
+	pub fn sign_mu(&self, mu: &[u8], hedged: bool) -> Result<Signature<P>, Error> {
+		self.secret.sign_mu(mu, hedged)
+	}
+
+	/// Verify a signature against a message representative; see [PublicKey::verify_mu].
+	pub fn verify_mu(&self, mu: &[u8], sig: &[u8]) -> Result<(), Error> {
+		self.public.verify_mu(mu, sig)
+	}
+}
+
+impl<P: ParameterSet> fmt::Debug for Keypair<P> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Keypair")
+			.field("secret", &self.secret)
+			.field("public", &self.public)
+			.finish()
+	}
+}
+
+/// Private key. Its bytes are overwritten with zeroes when it is dropped.
+#[derive(Clone)]
+pub struct SecretKey<P: ParameterSet> {
+	pub(crate) bytes: P::SecretKeyBytes,
+}
+
+impl<P: ParameterSet> Zeroize for SecretKey<P> {
+	fn zeroize(&mut self) {
+		self.bytes.zeroize();
+	}
+}
+
+impl<P: ParameterSet> Drop for SecretKey<P> {
+	fn drop(&mut self) {
+		self.zeroize();
+	}
+}
+
+impl<P: ParameterSet> ZeroizeOnDrop for SecretKey<P> {}
+
+impl<P: ParameterSet> fmt::Debug for SecretKey<P> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("SecretKey")
+			.field("algorithm", &P::ALGORITHM)
+			.finish_non_exhaustive()
+	}
+}
+
+impl<P: ParameterSet> SecretKey<P> {
+	/// Borrow the underlying bytes, which stay in the key and are wiped with it.
+	pub fn as_bytes(&self) -> &[u8] {
+		self.bytes.as_ref()
+	}
+
+	/// Returns a copy of underlying bytes. The copy is not wiped on drop.
+	pub fn to_bytes(&self) -> P::SecretKeyBytes {
+		self.bytes
+	}
+
+	/// Create a SecretKey from bytes.
+	///
+	/// # Arguments
+	///
+	/// * 'bytes' - private key bytes
+	///
+	/// Returns a SecretKey
+	pub fn from_bytes(bytes: &[u8]) -> Result<SecretKey<P>, KeyParsingError> {
+		match P::SecretKeyBytes::try_from_slice(bytes) {
+			Some(bytes) => Ok(SecretKey { bytes }),
+			None => Err(KeyParsingError::BadSecretKey),
+		}
+	}
+
+	/// Expand the secret key generated by the seed ξ; see [Keypair::from_seed].
+	pub fn from_seed(seed: &[u8; crate::params::SEEDBYTES]) -> SecretKey<P> {
+		Keypair::from_seed(seed).secret
+	}
+
+	/// Recompute the public key from rho, s1 and s2, checking on the way that the key is
+	/// consistent: the coefficients of s1 and s2 lie in [-ETA, ETA], t0 is the low part of
+	/// As1 + s2 and tr is the hash of the resulting public key. The seed K is not covered, as
+	/// any value is valid.
+	///
+	/// Returns the PublicKey, or [KeyParsingError::BadSecretKey] if a check fails
+	pub fn public_key(&self) -> Result<PublicKey<P>, KeyParsingError> {
+		let mut pk = P::PublicKeyBytes::zeroed();
+		if !P::public_from_secret(pk.as_mut(), self.bytes.as_ref()) {
+			return Err(KeyParsingError::BadSecretKey);
+		}
+		Ok(PublicKey { bytes: pk })
+	}
+
+	/// Start signing a message that is fed in chunks; see [Signer].
+	///
+	/// Returns [Error::ContextTooLong] if 'ctx' is longer than 255 bytes
+	pub fn signer(&self, ctx: Option<&[u8]>) -> Result<Signer<'_, P>, Error> {
+		Signer::new(self, ctx)
+	}
+
+	/// Expand this key for signing many messages; see [PreparedSecretKey].
+	pub fn prepare(&self) -> PreparedSecretKey<P> {
+		PreparedSecretKey::new(self)
+	}
+
+	/// Compute a signature for a given message.
+	///
+	/// # Arguments
+	///
+	/// * 'msg' - message to sign
+	/// * 'ctx' - context string
+	/// * 'hedged' - wether to use RNG or not
+	///
+	/// Returns Signature, or [Error::ContextTooLong] if 'ctx' is longer than 255 bytes
+	pub fn sign(
+		&self,
+		msg: &[u8],
+		ctx: Option<&[u8]>,
+		hedged: bool,
+	) -> Result<Signature<P>, Error> {
+		sign_with::<P>(self.bytes.as_ref(), msg, ctx, hedged)
+	}
+
+	/// Compute a hedged signature for a given message, drawing the randomness from `rng`
+	/// rather than the system RNG. This works without the default RNG, and a seeded 'rng'
+	/// makes hedged signatures reproducible.
+	///
+	/// # Arguments
+	///
+	/// * 'msg' - message to sign
+	/// * 'ctx' - context string
+	/// * 'rng' - source of the signing randomness
+	///
+	/// Returns Signature, or [Error::MissingRng] if 'rng' fails
+	pub fn sign_with_rng(
+		&self,
+		msg: &[u8],
+		ctx: Option<&[u8]>,
+		rng: &mut (impl RngCore + CryptoRng),
+	) -> Result<Signature<P>, Error> {
+		let mut signer = self.signer(ctx)?;
+		signer.update(msg);
+		signer.sign_with_rng(rng)
+	}
+
+	/// Compute the signature [SecretKey::sign] gives while regenerating A and the NTT forms of
+	/// s1, s2 and t0 as they are used, rather than expanding the key up front. Signing is
+	/// slower, but needs a few tens of KiB of stack instead of over 100 KiB for ML-DSA-87, which
+	/// suits small threads and embedded devices; see [crate::sign::signature_low_memory].
+	///
+	/// # Arguments
+	///
+	/// * 'msg' - message to sign
+	/// * 'ctx' - context string
+	/// * 'hedged' - wether to use RNG or not
+	///
+	/// Returns Signature, or [Error::ContextTooLong] if 'ctx' is longer than 255 bytes
+	pub fn sign_low_memory(
+		&self,
+		msg: &[u8],
+		ctx: Option<&[u8]>,
+		hedged: bool,
+	) -> Result<Signature<P>, Error> {
+		let sk = self.bytes.as_ref();
+		let mu = pure_mu(secret_tr(sk), ctx, msg)?;
+		let mut rnd = [0u8; crate::params::SEEDBYTES];
+		if hedged {
+			crate::random_bytes(&mut rnd, crate::params::SEEDBYTES)?;
+		}
+		let mut sig = P::SignatureBytes::zeroed();
+		P::sign_low_memory_mu(sk, sig.as_mut(), &mu, &rnd);
+		rnd.zeroize();
+		Ok(sig)
+	}
+
+	/// Compute a HashML-DSA signature for a given message.
+	///
+	/// # Arguments
+	///
+	/// * 'msg' - message to sign
+	/// * 'ctx' - context string
+	/// * 'hedged' - wether to use RNG or not
+	/// * 'ph' - pre-hash function
+	///
+	/// Returns Signature, or [Error::ContextTooLong] if 'ctx' is longer than 255 bytes
+	pub fn prehash_sign(
+		&self,
+		msg: &[u8],
+		ctx: Option<&[u8]>,
+		hedged: bool,
+		ph: PH,
+	) -> Result<Signature<P>, Error> {
+		prehash_sign_with::<P>(self.bytes.as_ref(), msg, ctx, hedged, ph)
+	}
+
+	/// Compute a HashML-DSA signature over a digest of the message computed elsewhere, for
+	/// a signer that never sees the message. The signature is the one [SecretKey::prehash_sign]
+	/// gives for the message.
+	///
+	/// # Arguments
+	///
+	/// * 'digest' - the message hashed with 'ph'
+	/// * 'ctx' - context string
+	/// * 'hedged' - wether to use RNG or not
+	/// * 'ph' - pre-hash function
+	///
+	/// Returns Signature, or [Error::BadLength] if 'digest' is not ph.digest_len() long
+	pub fn sign_prehashed(
+		&self,
+		digest: &[u8],
+		ctx: Option<&[u8]>,
+		hedged: bool,
+		ph: PH,
+	) -> Result<Signature<P>, Error> {
+		prehashed_sign_with::<P>(self.bytes.as_ref(), digest, ctx, hedged, ph)
+	}
+
+	/// Compute a signature from a message representative, for a signer that never sees the
+	/// message; see [PublicKey::message_representative].
+	///
+	/// # Arguments
+	///
+	/// * 'mu' - params::CRHBYTES message representative
+	/// * 'hedged' - wether to use RNG or not
+	///
+	/// Returns Signature, or [Error::BadLength] if 'mu' is not params::CRHBYTES long
+	pub fn sign_mu(&self, mu: &[u8], hedged: bool) -> Result<Signature<P>, Error> {
+		sign_mu_with::<P>(self.bytes.as_ref(), mu, hedged)
+	}
+}
+
+/// ML-DSA.Sign with the encoded secret key `sk`; shared by the key types that store it.
+pub(crate) fn sign_with<P: ParameterSet>(
+	sk: &[u8],
+	msg: &[u8],
+	ctx: Option<&[u8]>,
+	hedged: bool,
+) -> Result<Signature<P>, Error> {
+	check_len(sk.len(), P::SECRETKEYBYTES)?;
+	let mut signer = Signer::<P>::from_bytes(sk, ctx)?;
+	signer.update(msg);
+	signer.sign(hedged)
+}
+
+/// HashML-DSA.Sign with the encoded secret key `sk`.
+pub(crate) fn prehash_sign_with<P: ParameterSet>(
+	sk: &[u8],
+	msg: &[u8],
+	ctx: Option<&[u8]>,
+	hedged: bool,
+	ph: PH,
+) -> Result<Signature<P>, Error> {
+	let mut digest = [0u8; PH::MAX_DIGEST_BYTES];
+	let n = ph.digest(msg, &mut digest);
+	prehashed_sign_with::<P>(sk, &digest[..n], ctx, hedged, ph)
+}
+
+/// HashML-DSA.Sign over `digest`, the message already hashed with `ph`.
+pub(crate) fn prehashed_sign_with<P: ParameterSet>(
+	sk: &[u8],
+	digest: &[u8],
+	ctx: Option<&[u8]>,
+	hedged: bool,
+	ph: PH,
+) -> Result<Signature<P>, Error> {
+	check_len(sk.len(), P::SECRETKEYBYTES)?;
+	let mu = prehash_mu(secret_tr(sk), ctx, digest, ph)?;
+	sign_mu_with::<P>(sk, &mu, hedged)
+}
+
+/// ML-DSA.Sign from the message representative onwards, with the encoded secret key `sk`.
+pub(crate) fn sign_mu_with<P: ParameterSet>(
+	sk: &[u8],
+	mu: &[u8],
+	hedged: bool,
+) -> Result<Signature<P>, Error> {
+	let mut sig = P::SignatureBytes::zeroed();
+	crate::sign::sign_mu::<P>(sig.as_mut(), mu, sk, hedged)?;
+	Ok(sig)
+}
+
+#[derive(Eq, Clone, PartialEq, Debug, Hash, PartialOrd, Ord)]
+pub struct PublicKey<P: ParameterSet> {
+	pub(crate) bytes: P::PublicKeyBytes,
+}
+
+impl<P: ParameterSet> PublicKey<P> {
+	/// Borrow the underlying bytes.
+	pub fn as_bytes(&self) -> &[u8] {
+		self.bytes.as_ref()
+	}
+
+	/// Returns a copy of underlying bytes.
+	pub fn to_bytes(&self) -> P::PublicKeyBytes {
+		self.bytes
+	}
+
+	/// Create a PublicKey from bytes.
+	///
+	/// # Arguments
+	///
+	/// * 'bytes' - public key bytes
+	///
+	/// Returns a PublicKey
+	pub fn from_bytes(bytes: &[u8]) -> Result<PublicKey<P>, KeyParsingError> {
+		match P::PublicKeyBytes::try_from_slice(bytes) {
+			Some(bytes) => Ok(PublicKey { bytes }),
+			None => Err(KeyParsingError::BadPublicKey),
+		}
+	}
+
+	/// Expand this key for verifying many signatures; see [PreparedPublicKey].
+	pub fn prepare(&self) -> PreparedPublicKey<P> {
+		PreparedPublicKey::new(self.clone())
+	}
+
+	/// Verify a signature for a given message with a public key.
+	///
+	/// # Arguments
+	///
+	/// * 'msg' - message that is claimed to be signed
+	/// * 'sig' - signature to verify
+	/// * 'ctx' - context string
+	///
+	/// Returns Ok(()) if the verification process was successful, otherwise the reason it failed
+	pub fn verify(&self, msg: &[u8], sig: &[u8], ctx: Option<&[u8]>) -> Result<(), Error> {
+		let mut verifier = self.verifier(ctx)?;
+		verifier.update(msg);
+		verifier.verify(sig)
+	}
+
+	/// Start verifying a signature over a message that is fed in chunks; see [Verifier].
+	///
+	/// Returns [Error::ContextTooLong] if 'ctx' is longer than 255 bytes
+	pub fn verifier(&self, ctx: Option<&[u8]>) -> Result<Verifier<'_, P>, Error> {
+		Verifier::new(self, ctx)
+	}
+
+	/// Verify a HashML-DSA signature for a given message with a public key.
+	///
+	/// # Arguments
+	///
+	/// * 'msg' - message that is claimed to be signed
+	/// * 'sig' - signature to verify
+	/// * 'ctx' - context string
+	/// * 'ph' - pre-hash function
+	///
+	/// Returns Ok(()) if the verification process was successful, otherwise the reason it failed
+	pub fn prehash_verify(
+		&self,
+		msg: &[u8],
+		sig: &[u8],
+		ctx: Option<&[u8]>,
+		ph: PH,
+	) -> Result<(), Error> {
+		let mut digest = [0u8; PH::MAX_DIGEST_BYTES];
+		let n = ph.digest(msg, &mut digest);
+		self.verify_prehashed(&digest[..n], sig, ctx, ph)
+	}
+
+	/// Verify a HashML-DSA signature over a digest of the message computed elsewhere.
+	///
+	/// # Arguments
+	///
+	/// * 'digest' - the message hashed with 'ph'
+	/// * 'sig' - signature to verify
+	/// * 'ctx' - context string
+	/// * 'ph' - pre-hash function
+	///
+	/// Returns Ok(()) if the verification process was successful, otherwise the reason it failed
+	pub fn verify_prehashed(
+		&self,
+		digest: &[u8],
+		sig: &[u8],
+		ctx: Option<&[u8]>,
+		ph: PH,
+	) -> Result<(), Error> {
+		let tr = public_tr(self.bytes.as_ref());
+		let mu = prehash_mu(&tr, ctx, digest, ph)?;
+		self.verify_mu_with_tr(&tr, &mu, sig)
+	}
+
+	/// Compute the message representative mu = H(H(pk) || M') of an ML-DSA signature over
+	/// `msg`, so that a remote signer can sign it with [SecretKey::sign_mu] without seeing
+	/// the message.
+	///
+	/// # Arguments
+	///
+	/// * 'msg' - message to be signed
+	/// * 'ctx' - context string
+	///
+	/// Returns mu, or [Error::ContextTooLong] if 'ctx' is longer than 255 bytes
+	pub fn message_representative(
+		&self,
+		msg: &[u8],
+		ctx: Option<&[u8]>,
+	) -> Result<[u8; crate::params::CRHBYTES], Error> {
+		pure_mu(&public_tr(self.bytes.as_ref()), ctx, msg)
+	}
+
+	/// Verify a signature against a message representative; see
+	/// [PublicKey::message_representative].
+	///
+	/// # Arguments
+	///
+	/// * 'mu' - params::CRHBYTES message representative
+	/// * 'sig' - signature to verify
+	///
+	/// Returns Ok(()) if the verification process was successful, otherwise the reason it failed
+	pub fn verify_mu(&self, mu: &[u8], sig: &[u8]) -> Result<(), Error> {
+		crate::sign::verify_mu::<P>(sig, mu, self.bytes.as_ref())
+	}
+
+	/// As [PublicKey::verify_mu] for a caller that has already computed tr = H(pk), so that pk is
+	/// not hashed a second time.
+	pub(crate) fn verify_mu_with_tr(
+		&self,
+		tr: &[u8; crate::params::TR_BYTES],
+		mu: &[u8; crate::params::CRHBYTES],
+		sig: &[u8],
+	) -> Result<(), Error> {
+		P::verify_prepared_mu(&P::expand_public_key(self.bytes.as_ref(), tr), sig, mu)
+	}
+}
+
+/// Compare two byte strings without branching on their contents.
+pub(crate) fn ct_eq(a: &[u8], b: &[u8]) -> bool {
+	a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Default a missing context to the empty string and reject one longer than 255 bytes.
+pub(crate) fn check_ctx(ctx: Option<&[u8]>) -> Result<&[u8], Error> {
+	let ctx = ctx.unwrap_or(&[]);
+	if ctx.len() > 255 {
+		return Err(Error::ContextTooLong);
+	}
+	Ok(ctx)
+}
+
+/// Compute mu = H(tr || M') for M' = domain || |ctx| || ctx || oid || msg, absorbing each part
+/// of M' into the sponge rather than building it in memory. Assumes ctx is at most 255 bytes.
+pub(crate) fn message_mu(
+	tr: &[u8],
+	domain: u8,
+	ctx: &[u8],
+	oid: &[u8],
+	msg: &[u8],
+) -> [u8; crate::params::CRHBYTES] {
+	let mut state = start_mu(tr, domain, ctx, oid);
+	fips202::shake256_absorb(&mut state, msg, msg.len());
+	let mu = finish_mu(&mut state);
+	state.zeroize();
+	mu
+}
+
+/// The ML-DSA mu of `msg` under the key with hash `tr`.
+pub(crate) fn pure_mu(
+	tr: &[u8],
+	ctx: Option<&[u8]>,
+	msg: &[u8],
+) -> Result<[u8; crate::params::CRHBYTES], Error> {
+	Ok(message_mu(tr, 0, check_ctx(ctx)?, &[], msg))
+}
+
+/// The HashML-DSA mu of `digest`, the message already hashed with `ph`.
+pub(crate) fn prehash_mu(
+	tr: &[u8],
+	ctx: Option<&[u8]>,
+	digest: &[u8],
+	ph: PH,
+) -> Result<[u8; crate::params::CRHBYTES], Error> {
+	let ctx = check_ctx(ctx)?;
+	check_len(digest.len(), ph.digest_len())?;
+	Ok(message_mu(tr, 1, ctx, &ph.oid(), digest))
+}
+
+#[cfg(test)]
+#[cfg(feature = "std")]
+mod tests {
+	use super::*;
+
+	fn round_trip<P: ParameterSet>() {
+		let keys = Keypair::<P>::generate(Some(&[3u8; crate::params::SEEDBYTES])).unwrap();
+		let msg = b"generic over the parameter set";
+		let sig = keys.sign(msg, Some(b"ctx"), false).unwrap();
+		assert_eq!(sig.as_ref().len(), P::SIGNBYTES);
+		assert!(keys.verify(msg, sig.as_ref(), Some(b"ctx")).is_ok());
+		assert_eq!(keys.verify(msg, sig.as_ref(), None), Err(Error::ChallengeMismatch));
+
+		let restored = Keypair::<P>::from_bytes(keys.to_bytes().as_ref()).unwrap();
+		assert_eq!(restored.public, keys.public);
+		assert_eq!(restored.sign(msg, Some(b"ctx"), false).unwrap(), sig);
+		assert_eq!(keys.sign_low_memory(msg, Some(b"ctx"), false).unwrap(), sig);
+
+		let sig = keys.prehash_sign(msg, None, false, crate::PH::SHA512).unwrap();
+		assert!(keys.prehash_verify(msg, sig.as_ref(), None, crate::PH::SHA512).is_ok());
+		assert!(keys.prehash_verify(msg, sig.as_ref(), None, crate::PH::SHA256).is_err());
+	}
+
+	fn checked_keypair<P: ParameterSet>() {
+		use crate::params::{SEEDBYTES, TR_BYTES};
+
+		let keys = Keypair::<P>::generate(Some(&[8u8; SEEDBYTES])).unwrap();
+		assert_eq!(keys.secret.public_key().unwrap(), keys.public);
+		let bytes = keys.to_bytes();
+		assert_eq!(Keypair::<P>::from_bytes_checked(bytes.as_ref()).unwrap().public, keys.public);
+
+		let corrupted = |index: usize, mask: u8| {
+			let mut bytes = bytes;
+			bytes.as_mut()[index] ^= mask;
+			assert!(Keypair::<P>::from_bytes(bytes.as_ref()).is_ok());
+			Keypair::<P>::from_bytes_checked(bytes.as_ref()).map(|_| ())
+		};
+		// The first coefficient of s1 is ETA minus its low 3 (ETA = 2) or 4 (ETA = 4) bits; all
+		// ones puts it below -ETA.
+		let s1 = 2 * SEEDBYTES + TR_BYTES;
+		let low = if P::ETA == 2 { 0x07 } else { 0x0f };
+		let s1_mask = (bytes.as_ref()[s1] & low) ^ low;
+		// rho, tr, s1 and t0.
+		for (index, mask) in [(0, 1), (2 * SEEDBYTES, 1), (s1, s1_mask), (P::SECRETKEYBYTES - 1, 1)]
+		{
+			assert!(matches!(corrupted(index, mask), Err(KeyParsingError::BadSecretKey)));
+		}
+		// The public key on its own, rho or t1.
+		for index in [P::SECRETKEYBYTES, P::KEYPAIRBYTES - 1] {
+			assert!(matches!(corrupted(index, 1), Err(KeyParsingError::BadKeypair)));
+		}
+		// K is not bound to anything else.
+		assert!(corrupted(SEEDBYTES, 1).is_ok());
+
+		let other = Keypair::<P>::generate(Some(&[9u8; SEEDBYTES])).unwrap();
+		let mixed = [keys.secret.bytes.as_ref(), other.public.bytes.as_ref()].concat();
+		assert!(matches!(
+			Keypair::<P>::from_bytes_checked(&mixed),
+			Err(KeyParsingError::BadKeypair)
+		));
+		assert!(matches!(
+			Keypair::<P>::from_bytes_checked(&mixed[1..]),
+			Err(KeyParsingError::BadKeypair)
+		));
+	}
+
+	fn prehash_every_function<P: ParameterSet>() {
+		let keys = Keypair::<P>::generate(Some(&[5u8; crate::params::SEEDBYTES])).unwrap();
+		let msg = b"hashed before signing";
+		for ph in PH::ALL {
+			let sig = keys.prehash_sign(msg, Some(b"ctx"), false, ph).unwrap();
+			assert!(keys.prehash_verify(msg, sig.as_ref(), Some(b"ctx"), ph).is_ok(), "{ph:?}");
+
+			let mut digest = [0u8; PH::MAX_DIGEST_BYTES];
+			let n = ph.digest(msg, &mut digest);
+			assert_eq!(n, ph.digest_len());
+			assert_eq!(keys.sign_prehashed(&digest[..n], Some(b"ctx"), false, ph).unwrap(), sig);
+			assert!(keys.verify_prehashed(&digest[..n], sig.as_ref(), Some(b"ctx"), ph).is_ok());
+			assert_eq!(
+				keys.sign_prehashed(&digest[..n - 1], None, false, ph),
+				Err(Error::BadLength { expected: n, actual: n - 1 })
+			);
+			assert_eq!(
+				keys.verify_prehashed(&digest, sig.as_ref(), Some(b"ctx"), ph).is_ok(),
+				n == PH::MAX_DIGEST_BYTES
+			);
+
+			// The OID binds the signature to the hash function.
+			for other in PH::ALL.into_iter().filter(|&other| other != ph) {
+				assert!(keys.prehash_verify(msg, sig.as_ref(), Some(b"ctx"), other).is_err());
+			}
+		}
+	}
+
+	/// An RNG that always fails, like a TRNG that has not started.
+	struct FailingRng;
+
+	impl RngCore for FailingRng {
+		fn next_u32(&mut self) -> u32 {
+			unimplemented!()
+		}
+		fn next_u64(&mut self) -> u64 {
+			unimplemented!()
+		}
+		fn fill_bytes(&mut self, _dest: &mut [u8]) {
+			unimplemented!()
+		}
+		fn try_fill_bytes(&mut self, _dest: &mut [u8]) -> Result<(), rand_core::Error> {
+			Err(core::num::NonZeroU32::new(rand_core::Error::CUSTOM_START).unwrap().into())
+		}
+	}
+
+	impl CryptoRng for FailingRng {}
+
+	fn caller_rng<P: ParameterSet>() {
+		use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+
+		let keys = Keypair::<P>::generate_with_rng(&mut ChaCha20Rng::seed_from_u64(1)).unwrap();
+		let again = Keypair::<P>::generate_with_rng(&mut ChaCha20Rng::seed_from_u64(1)).unwrap();
+		assert_eq!(keys.to_bytes(), again.to_bytes());
+
+		let msg = b"hedged but reproducible";
+		let sig = keys
+			.sign_with_rng(msg, Some(b"ctx"), &mut ChaCha20Rng::seed_from_u64(2))
+			.unwrap();
+		assert!(keys.verify(msg, sig.as_ref(), Some(b"ctx")).is_ok());
+		assert_ne!(sig, keys.sign(msg, Some(b"ctx"), false).unwrap());
+		assert_ne!(
+			sig,
+			keys.sign_with_rng(msg, Some(b"ctx"), &mut ChaCha20Rng::seed_from_u64(3))
+				.unwrap()
+		);
+
+		// Every signing path draws the same randomness from the same RNG state.
+		let prepared = keys.secret.prepare();
+		let mut rng = ChaCha20Rng::seed_from_u64(2);
+		assert_eq!(prepared.sign_with_rng(msg, Some(b"ctx"), &mut rng).unwrap(), sig);
+		let mut signer = keys.secret.signer(Some(b"ctx")).unwrap();
+		signer.update(msg);
+		assert_eq!(signer.sign_with_rng(&mut ChaCha20Rng::seed_from_u64(2)).unwrap(), sig);
+
+		assert_eq!(
+			Keypair::<P>::generate_with_rng(&mut FailingRng).map(|_| ()),
+			Err(Error::MissingRng)
+		);
+		assert_eq!(keys.sign_with_rng(msg, None, &mut FailingRng), Err(Error::MissingRng));
+		assert_eq!(prepared.sign_with_rng(msg, None, &mut FailingRng), Err(Error::MissingRng));
+	}
+
+	fn bad_lengths<P: ParameterSet>() {
+		let keys = Keypair::<P>::generate(None).unwrap();
+		let bytes = keys.to_bytes();
+		assert!(matches!(
+			Keypair::<P>::from_bytes(&bytes.as_ref()[1..]),
+			Err(KeyParsingError::BadKeypair)
+		));
+		assert!(matches!(
+			SecretKey::<P>::from_bytes(&bytes.as_ref()[..P::SECRETKEYBYTES - 1]),
+			Err(KeyParsingError::BadSecretKey)
+		));
+		assert!(matches!(
+			PublicKey::<P>::from_bytes(&bytes.as_ref()[1..P::SECRETKEYBYTES]),
+			Err(KeyParsingError::BadPublicKey)
+		));
+		let sig = keys.sign(b"", None, true).unwrap();
+		assert_eq!(
+			keys.verify(b"", &sig.as_ref()[1..], None),
+			Err(Error::BadLength { expected: P::SIGNBYTES, actual: P::SIGNBYTES - 1 })
+		);
+		assert_eq!(
+			Keypair::<P>::generate(Some(&[0u8; crate::params::SEEDBYTES - 1])).map(|_| ()),
+			Err(Error::BadLength { expected: crate::params::SEEDBYTES, actual: 31 })
+		);
+	}
+
+	fn rejections<P: ParameterSet>() {
+		let keys = Keypair::<P>::generate(None).unwrap();
+		let long_ctx = [0u8; 256];
+		assert_eq!(keys.sign(b"msg", Some(&long_ctx), false), Err(Error::ContextTooLong));
+		assert_eq!(
+			keys.prehash_sign(b"msg", Some(&long_ctx), false, crate::PH::SHA256),
+			Err(Error::ContextTooLong)
+		);
+
+		let sig = keys.sign(b"msg", Some(&long_ctx[..255]), false).unwrap();
+		assert!(keys.verify(b"msg", sig.as_ref(), Some(&long_ctx[..255])).is_ok());
+		assert_eq!(keys.verify(b"msg", sig.as_ref(), Some(&long_ctx)), Err(Error::ContextTooLong));
+
+		// The first z coefficient packs to GAMMA1 - 0xff..., far outside GAMMA1 - BETA.
+		let mut bad = sig;
+		bad.as_mut()[P::C_DASH_BYTES..P::C_DASH_BYTES + 3].fill(0xff);
+		assert_eq!(keys.verify(b"msg", bad.as_ref(), None), Err(Error::NormBoundExceeded));
+
+		// A hint count above OMEGA is not a valid encoding.
+		let mut bad = sig;
+		bad.as_mut()[P::SIGNBYTES - 1] = 0xff;
+		assert_eq!(keys.verify(b"msg", bad.as_ref(), None), Err(Error::HintCountOutOfRange));
+	}
+
+	/// Every way of re-encoding a valid signature is rejected, by verification and by
+	/// [crate::sign::check_signature] alike.
+	fn strict_decoding<P: ParameterSet>() {
+		let keys = Keypair::<P>::generate(Some(&[3u8; crate::params::SEEDBYTES])).unwrap();
+		let sig = keys.sign(b"msg", None, false).unwrap();
+		let check = crate::sign::check_signature::<P>;
+		assert_eq!(check(sig.as_ref()), Ok(()));
+
+		let rejected = |bad: &[u8], err: Error| {
+			assert_eq!(check(bad), Err(err));
+			assert_eq!(keys.verify(b"msg", bad, None), Err(err));
+		};
+		let mut long = [0u8; crate::params::ml_dsa_87::SIGNBYTES + 1];
+		long[..P::SIGNBYTES].copy_from_slice(sig.as_ref());
+		let expected = P::SIGNBYTES;
+		rejected(&long[..expected + 1], Error::BadLength { expected, actual: expected + 1 });
+		rejected(
+			&sig.as_ref()[..expected - 1],
+			Error::BadLength { expected, actual: expected - 1 },
+		);
+
+		// The hint is OMEGA positions followed by K cumulative counts.
+		let hint = P::SIGNBYTES - P::OMEGA - P::K;
+		let counts: Vec<usize> =
+			sig.as_ref()[hint + P::OMEGA..].iter().map(|&n| n as usize).collect();
+		let mut bad = sig;
+		bad.as_mut()[hint + P::OMEGA] = P::OMEGA as u8 + 1;
+		rejected(bad.as_ref(), Error::HintCountOutOfRange);
+		assert!(counts[P::K - 2] > 0 && counts[P::K - 1] < P::OMEGA);
+		let mut bad = sig;
+		bad.as_mut()[P::SIGNBYTES - 1] = counts[P::K - 2] as u8 - 1;
+		rejected(bad.as_ref(), Error::HintCountOutOfRange);
+
+		let mut bad = sig;
+		bad.as_mut()[hint + P::OMEGA - 1] = 1;
+		rejected(bad.as_ref(), Error::HintPaddingNotZero);
+
+		// Swap or repeat the first two positions of a row holding at least two hints.
+		let first = |i: usize| if i == 0 { 0 } else { counts[i - 1] };
+		let row = (0..P::K).find(|&i| counts[i] - first(i) >= 2).unwrap();
+		let start = hint + first(row);
+		let mut bad = sig;
+		bad.as_mut().swap(start, start + 1);
+		rejected(bad.as_ref(), Error::UnsortedHint);
+		let mut bad = sig;
+		bad.as_mut()[start + 1] = bad.as_ref()[start];
+		rejected(bad.as_ref(), Error::UnsortedHint);
+
+		// Public keys have no redundant encodings, so only the length can be wrong.
+		let pk = keys.public.to_bytes();
+		let mut long = [0u8; crate::params::ml_dsa_87::PUBLICKEYBYTES + 1];
+		long[..P::PUBLICKEYBYTES].copy_from_slice(pk.as_ref());
+		assert!(PublicKey::<P>::from_bytes(&long[..P::PUBLICKEYBYTES]).is_ok());
+		assert!(matches!(
+			PublicKey::<P>::from_bytes(&long[..P::PUBLICKEYBYTES + 1]),
+			Err(KeyParsingError::BadPublicKey)
+		));
+		assert!(matches!(
+			PublicKey::<P>::from_bytes(&pk.as_ref()[1..]),
+			Err(KeyParsingError::BadPublicKey)
+		));
+	}
+
+	#[test]
+	fn strict_decoding_all_levels() {
+		strict_decoding::<MlDsa44>();
+		strict_decoding::<MlDsa65>();
+		strict_decoding::<MlDsa87>();
+	}
+
+	#[test]
+	fn checked_keypair_all_levels() {
+		checked_keypair::<MlDsa44>();
+		checked_keypair::<MlDsa65>();
+		checked_keypair::<MlDsa87>();
+	}
+
+	#[test]
+	fn round_trip_all_levels() {
+		round_trip::<MlDsa44>();
+		round_trip::<MlDsa65>();
+		round_trip::<MlDsa87>();
+	}
+
+	#[test]
+	fn prehash_every_function_all_levels() {
+		prehash_every_function::<MlDsa44>();
+		prehash_every_function::<MlDsa65>();
+		prehash_every_function::<MlDsa87>();
+	}
+
+	#[test]
+	fn caller_rng_all_levels() {
+		caller_rng::<MlDsa44>();
+		caller_rng::<MlDsa65>();
+		caller_rng::<MlDsa87>();
+	}
+
+	#[test]
+	fn bad_lengths_all_levels() {
+		bad_lengths::<MlDsa44>();
+		bad_lengths::<MlDsa65>();
+		bad_lengths::<MlDsa87>();
+	}
+
+	#[test]
+	fn rejections_all_levels() {
+		rejections::<MlDsa44>();
+		rejections::<MlDsa65>();
+		rejections::<MlDsa87>();
+	}
+
+	/// Drop `value` in place and return the memory it occupied, to check that a type wipes its
+	/// secrets when dropped. Only for types without padding, whose bytes are all initialized.
+	fn memory_after_drop<T>(value: T) -> Vec<u8> {
+		let mut slot = core::mem::MaybeUninit::new(value);
+		// SAFETY: the slot is initialized and dropped exactly once; the bytes read back afterwards
+		// stay initialized, since dropping does not deallocate inline storage.
+		unsafe {
+			slot.assume_init_drop();
+			core::slice::from_raw_parts(slot.as_ptr() as *const u8, core::mem::size_of::<T>())
+				.to_vec()
+		}
+	}
+
+	fn wiped_on_drop<P: ParameterSet>() {
+		let keys = Keypair::<P>::generate(None).unwrap();
+		let public = keys.public.clone();
+		assert!(keys.secret.as_bytes().iter().any(|&b| b != 0));
+
+		let memory = memory_after_drop(keys.secret.clone());
+		assert!(memory.iter().all(|&b| b == 0));
+
+		// The secret half of a keypair is wiped, the public half is left alone.
+		let memory = memory_after_drop(keys);
+		assert_eq!(memory.len(), P::SECRETKEYBYTES + P::PUBLICKEYBYTES);
+		assert!(memory[..P::SECRETKEYBYTES].iter().all(|&b| b == 0));
+		assert_eq!(&memory[P::SECRETKEYBYTES..], public.bytes.as_ref());
+	}
+
+	#[test]
+	fn wiped_on_drop_all_levels() {
+		wiped_on_drop::<MlDsa44>();
+		wiped_on_drop::<MlDsa65>();
+		wiped_on_drop::<MlDsa87>();
+	}
+
+	#[test]
+	fn debug_redacts_secret() {
+		let keys = Keypair::<MlDsa44>::generate(Some(&[9u8; crate::params::SEEDBYTES])).unwrap();
+		let debug = format!("{keys:?}");
+		assert!(debug.contains("SecretKey { algorithm: MlDsa44, .. }"));
+		// The first 32 bytes are rho, which the public key shares; check the private K instead.
+		let key = format!("{:?}", &keys.secret.bytes.as_ref()[32..40]);
+		assert!(!debug.contains(&key[1..key.len() - 1]));
+	}
+
+	#[test]
+	fn sizes_match_params() {
+		assert_eq!(MlDsa44::PUBLICKEYBYTES, crate::params::ml_dsa_44::PUBLICKEYBYTES);
+		assert_eq!(MlDsa65::SECRETKEYBYTES, crate::params::ml_dsa_65::SECRETKEYBYTES);
+		assert_eq!(MlDsa87::SIGNBYTES, crate::params::ml_dsa_87::SIGNBYTES);
+		assert_eq!(MlDsa87::KEYPAIRBYTES, crate::ml_dsa_87::KEYPAIRBYTES);
+	}
+}
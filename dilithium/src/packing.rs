@@ -1,6 +1,176 @@
+use crate::{errors::Error, ml_dsa::ParameterSet, params, poly, polyvec::Polyvec};
+const N: usize = params::N as usize;
+
 pub mod lvl2;
 pub mod lvl3;
 pub mod lvl5;
-pub mod ml_dsa_44;
-pub mod ml_dsa_65;
-pub mod ml_dsa_87;
+
+/// Bit-pack public key pk = (rho, t1).
+///
+/// # Arguments
+///
+/// * 'pk' - output for public key value
+/// * 'rho' - const reference to rho of params::SEEDBYTES length
+/// * 't1' - const reference to t1
+pub fn pack_pk<const K: usize>(pk: &mut [u8], rho: &[u8], t1: &Polyvec<K>) {
+	pk[..params::SEEDBYTES].copy_from_slice(&rho[..params::SEEDBYTES]);
+	for i in 0..K {
+		poly::t1_pack(&mut pk[params::SEEDBYTES + i * params::POLYT1_PACKEDBYTES..], &t1.vec[i]);
+	}
+}
+
+/// Unpack public key pk = (rho, t1).
+///
+/// # Arguments
+///
+/// * 'rho' - output for rho value of params::SEEDBYTES length
+/// * 't1' - output for t1 value
+/// * 'pk' - const reference to public key
+pub fn unpack_pk<const K: usize>(rho: &mut [u8], t1: &mut Polyvec<K>, pk: &[u8]) {
+	rho[..params::SEEDBYTES].copy_from_slice(&pk[..params::SEEDBYTES]);
+	for i in 0..K {
+		poly::t1_unpack(&mut t1.vec[i], &pk[params::SEEDBYTES + i * params::POLYT1_PACKEDBYTES..]);
+	}
+}
+
+/// Bit-pack secret key sk = (rho, key, tr, s1, s2, t0).
+pub fn pack_sk<P: ParameterSet, const K: usize, const L: usize>(
+	sk: &mut [u8],
+	rho: &[u8],
+	tr: &[u8],
+	key: &[u8],
+	t0: &Polyvec<K>,
+	s1: &Polyvec<L>,
+	s2: &Polyvec<K>,
+) {
+	sk[..params::SEEDBYTES].copy_from_slice(&rho[0..params::SEEDBYTES]);
+	let mut idx = params::SEEDBYTES;
+
+	sk[idx..idx + params::SEEDBYTES].copy_from_slice(&key[0..params::SEEDBYTES]);
+	idx += params::SEEDBYTES;
+
+	sk[idx..idx + params::TR_BYTES].copy_from_slice(&tr[0..params::TR_BYTES]);
+	idx += params::TR_BYTES;
+
+	for i in 0..L {
+		P::eta_pack(&mut sk[idx + i * P::POLYETA_PACKEDBYTES..], &s1.vec[i]);
+	}
+	idx += L * P::POLYETA_PACKEDBYTES;
+
+	for i in 0..K {
+		P::eta_pack(&mut sk[idx + i * P::POLYETA_PACKEDBYTES..], &s2.vec[i]);
+	}
+	idx += K * P::POLYETA_PACKEDBYTES;
+
+	for i in 0..K {
+		poly::t0_pack(&mut sk[idx + i * params::POLYT0_PACKEDBYTES..], &t0.vec[i]);
+	}
+}
+
+/// Unpack secret key sk = (rho, key, tr, s1, s2, t0).
+pub fn unpack_sk<P: ParameterSet, const K: usize, const L: usize>(
+	rho: &mut [u8],
+	tr: &mut [u8],
+	key: &mut [u8],
+	t0: &mut Polyvec<K>,
+	s1: &mut Polyvec<L>,
+	s2: &mut Polyvec<K>,
+	sk: &[u8],
+) {
+	rho[..params::SEEDBYTES].copy_from_slice(&sk[..params::SEEDBYTES]);
+	let mut idx = params::SEEDBYTES;
+
+	key[..params::SEEDBYTES].copy_from_slice(&sk[idx..idx + params::SEEDBYTES]);
+	idx += params::SEEDBYTES;
+
+	tr[..params::TR_BYTES].copy_from_slice(&sk[idx..idx + params::TR_BYTES]);
+	idx += params::TR_BYTES;
+
+	for i in 0..L {
+		P::eta_unpack(&mut s1.vec[i], &sk[idx + i * P::POLYETA_PACKEDBYTES..]);
+	}
+	idx += L * P::POLYETA_PACKEDBYTES;
+
+	for i in 0..K {
+		P::eta_unpack(&mut s2.vec[i], &sk[idx + i * P::POLYETA_PACKEDBYTES..]);
+	}
+	idx += K * P::POLYETA_PACKEDBYTES;
+
+	for i in 0..K {
+		poly::t0_unpack(&mut t0.vec[i], &sk[idx + i * params::POLYT0_PACKEDBYTES..]);
+	}
+}
+
+/// Bit-pack signature sig = (c, z, h).
+pub fn pack_sig<P: ParameterSet, const K: usize, const L: usize>(
+	sig: &mut [u8],
+	c: Option<&[u8]>,
+	z: &Polyvec<L>,
+	h: &Polyvec<K>,
+) {
+	if let Some(challenge) = c {
+		sig[..P::C_DASH_BYTES].copy_from_slice(&challenge[..P::C_DASH_BYTES]);
+	}
+
+	let mut idx = P::C_DASH_BYTES;
+	for i in 0..L {
+		P::z_pack(&mut sig[idx + i * P::POLYZ_PACKEDBYTES..], &z.vec[i]);
+	}
+
+	idx += L * P::POLYZ_PACKEDBYTES;
+	sig[idx..idx + P::OMEGA + K].fill(0);
+
+	let mut k = 0;
+	for i in 0..K {
+		for j in 0..N {
+			if h.vec[i].coeffs[j] != 0 {
+				sig[idx + k] = j as u8;
+				k += 1;
+			}
+		}
+		sig[idx + P::OMEGA + i] = k as u8;
+	}
+}
+
+/// Unpack signature sig = (z, h, c), accepting only the canonical encoding of the hint. Every z
+/// bit pattern decodes to a distinct coefficient, so z needs no check here; values past the norm
+/// bound are rejected by verification.
+///
+/// Returns [Error::HintCountOutOfRange], [Error::UnsortedHint] or [Error::HintPaddingNotZero] if
+/// the hint is not a canonical encoding.
+pub fn unpack_sig<P: ParameterSet, const K: usize, const L: usize>(
+	c: &mut [u8],
+	z: &mut Polyvec<L>,
+	h: &mut Polyvec<K>,
+	sig: &[u8],
+) -> Result<(), Error> {
+	c[..P::C_DASH_BYTES].copy_from_slice(&sig[..P::C_DASH_BYTES]);
+
+	let mut idx = P::C_DASH_BYTES;
+	for i in 0..L {
+		P::z_unpack(&mut z.vec[i], &sig[idx + i * P::POLYZ_PACKEDBYTES..]);
+	}
+	idx += L * P::POLYZ_PACKEDBYTES;
+
+	let mut k: usize = 0;
+	for i in 0..K {
+		if sig[idx + P::OMEGA + i] < k as u8 || sig[idx + P::OMEGA + i] > P::OMEGA as u8 {
+			return Err(Error::HintCountOutOfRange);
+		}
+		for j in k..sig[idx + P::OMEGA + i] as usize {
+			if j > k && sig[idx + j] <= sig[idx + j - 1] {
+				return Err(Error::UnsortedHint);
+			}
+			h.vec[i].coeffs[sig[idx + j] as usize] = 1;
+		}
+		k = sig[idx + P::OMEGA + i] as usize;
+	}
+
+	for j in k..P::OMEGA {
+		if sig[idx + j] > 0 {
+			return Err(Error::HintPaddingNotZero);
+		}
+	}
+
+	Ok(())
+}
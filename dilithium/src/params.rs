@@ -10,6 +10,9 @@ pub const CRHBYTES: usize = 64;
 pub const POLYT1_PACKEDBYTES: usize = 320;
 pub const POLYT0_PACKEDBYTES: usize = 416;
 pub const TR_BYTES: usize = 64;
+// Upper bounds over the ML-DSA parameter sets, sizing buffers in code generic over the level
+pub const C_DASH_BYTES_MAX: usize = ml_dsa_87::C_DASH_BYTES;
+pub const POLYVECK_W1_PACKEDBYTES_MAX: usize = ml_dsa_87::K * ml_dsa_87::POLYW1_PACKEDBYTES;
 
 // Specific security levels parameters
 pub mod lvl2;
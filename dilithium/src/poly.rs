@@ -9,6 +9,12 @@ pub struct Poly {
 	pub coeffs: [i32; N],
 }
 
+impl zeroize::Zeroize for Poly {
+	fn zeroize(&mut self) {
+		self.coeffs.zeroize();
+	}
+}
+
 /// For some reason can't simply derive the Default trait
 impl Default for Poly {
 	fn default() -> Self {
@@ -16,7 +22,7 @@ impl Default for Poly {
 	}
 }
 
-/// Inplace reduction of all coefficients of polynomial to representative in [-6283009,6283007].
+/// Inplace reduction of all coefficients of polynomial to representative in [-6283009,6283008].
 pub fn reduce(a: &mut Poly) {
 	// Bad C style
 	// for i in 0..N {
@@ -147,8 +147,19 @@ pub fn challenge(c: &mut Poly, seed: &[u8]) {
 				break;
 			}
 		}
-		c.coeffs[i] = c.coeffs[b];
-		c.coeffs[b] = 1 - 2 * ((signs & 1) as i32);
+		// c[i] = c[b], then c[b] = +-1, touching every coefficient up to i so that the memory
+		// accesses do not depend on b: c~ of a rejected attempt is never published.
+		let sign = 1 - 2 * ((signs & 1) as i32);
+		let mut moved = 0;
+		for j in 0..=i {
+			let mask = -((((j ^ b) as u32).wrapping_sub(1) >> 31) as i32);
+			moved |= c.coeffs[j] & mask;
+		}
+		c.coeffs[i] = moved;
+		for j in 0..=i {
+			let mask = -((((j ^ b) as u32).wrapping_sub(1) >> 31) as i32);
+			c.coeffs[j] ^= (c.coeffs[j] ^ sign) & mask;
+		}
 		signs >>= 1;
 	}
 }
@@ -1,3 +1,187 @@
+use crate::{ml_dsa::ParameterSet, poly, poly::Poly};
+use core::mem::swap;
+
 pub mod lvl2;
 pub mod lvl3;
 pub mod lvl5;
+
+/// Vector of N polynomials. ML-DSA uses vectors of length K (rows of A) and L (columns of A).
+#[derive(Clone, Copy)]
+pub struct Polyvec<const N: usize> {
+	pub vec: [Poly; N],
+}
+
+impl<const N: usize> Default for Polyvec<N> {
+	fn default() -> Self {
+		Polyvec { vec: [Poly::default(); N] }
+	}
+}
+
+impl<const N: usize> zeroize::Zeroize for Polyvec<N> {
+	fn zeroize(&mut self) {
+		self.vec.zeroize();
+	}
+}
+
+/// Implementation of ExpandA. Generates matrix A with uniformly random coefficients a_{i,j} by
+/// performing rejection sampling on the output stream of SHAKE128(rho|j|i).
+pub fn matrix_expand<const K: usize, const L: usize>(mat: &mut [Polyvec<L>; K], rho: &[u8]) {
+	for (i, mat_i) in mat.iter_mut().enumerate() {
+		for j in 0..L {
+			poly::uniform(&mut mat_i.vec[j], rho, ((i << 8) + j) as u16);
+		}
+	}
+}
+
+/// Pointwise multiply vectors of polynomials of length L, multiply resulting vector by 2^{-32} and
+/// add (accumulate) polynomials in it. Input/output vectors are in NTT domain representation. Input
+/// coefficients are assumed to be less than 22*Q. Output coeffcient are less than 2*L*Q.
+pub fn pointwise_acc_montgomery<const L: usize>(w: &mut Poly, u: &Polyvec<L>, v: &Polyvec<L>) {
+	poly::pointwise_montgomery(w, &u.vec[0], &v.vec[0]);
+	let mut t = Poly::default();
+	for i in 1..L {
+		poly::pointwise_montgomery(&mut t, &u.vec[i], &v.vec[i]);
+		poly::add_ip(w, &t);
+	}
+}
+
+pub fn matrix_pointwise_montgomery<const K: usize, const L: usize>(
+	t: &mut Polyvec<K>,
+	mat: &[Polyvec<L>; K],
+	v: &Polyvec<L>,
+) {
+	for (t_i, mat_i) in t.vec.iter_mut().zip(mat.iter()) {
+		pointwise_acc_montgomery(t_i, mat_i, v);
+	}
+}
+
+/// Sample a vector with coefficients in [-ETA, ETA]; the i-th polynomial uses nonce + i.
+pub fn uniform_eta<P: ParameterSet, const N: usize>(v: &mut Polyvec<N>, seed: &[u8], nonce: u16) {
+	for i in 0..N {
+		P::uniform_eta(&mut v.vec[i], seed, nonce + i as u16);
+	}
+}
+
+/// Sample the masking vector y with coefficients in [-(GAMMA1 - 1), GAMMA1 - 1].
+pub fn uniform_gamma1<P: ParameterSet, const L: usize>(
+	v: &mut Polyvec<L>,
+	seed: &[u8],
+	nonce: u16,
+) {
+	for i in 0..L {
+		P::uniform_gamma1(&mut v.vec[i], seed, L as u16 * nonce + i as u16);
+	}
+}
+
+/// Reduce coefficients of polynomials in vector to representatives in \[-6283009,6283008\].
+pub fn reduce<const N: usize>(v: &mut Polyvec<N>) {
+	for i in 0..N {
+		poly::reduce(&mut v.vec[i]);
+	}
+}
+
+/// For all coefficients of polynomials in vector add Q if coefficient is negative.
+pub fn caddq<const N: usize>(v: &mut Polyvec<N>) {
+	for i in 0..N {
+		poly::caddq(&mut v.vec[i]);
+	}
+}
+
+/// Add vectors of polynomials. No modular reduction is performed.
+pub fn add<const N: usize>(w: &mut Polyvec<N>, v: &Polyvec<N>) {
+	for i in 0..N {
+		poly::add_ip(&mut w.vec[i], &v.vec[i]);
+	}
+}
+
+/// Subtract vectors of polynomials. Assumes coefficients of polynomials in second input vector to
+/// be less than 2*Q. No modular reduction is performed.
+pub fn sub<const N: usize>(w: &mut Polyvec<N>, v: &Polyvec<N>) {
+	for i in 0..N {
+		poly::sub_ip(&mut w.vec[i], &v.vec[i]);
+	}
+}
+
+/// Multiply vector of polynomials by 2^D without modular reduction. Assumes input coefficients to
+/// be less than 2^{32-D}.
+pub fn shiftl<const N: usize>(v: &mut Polyvec<N>) {
+	for i in 0..N {
+		poly::shiftl(&mut v.vec[i]);
+	}
+}
+
+/// Forward NTT of all polynomials in vector. Output coefficients can be up to 16*Q larger than
+/// input coefficients.
+pub fn ntt<const N: usize>(v: &mut Polyvec<N>) {
+	for i in 0..N {
+		poly::ntt(&mut v.vec[i]);
+	}
+}
+
+/// Inverse NTT and multiplication by 2^{32} of polynomials in vector. Input coefficients need to
+/// be less than 2*Q.
+pub fn invntt_tomont<const N: usize>(v: &mut Polyvec<N>) {
+	for i in 0..N {
+		poly::invntt_tomont(&mut v.vec[i]);
+	}
+}
+
+pub fn pointwise_poly_montgomery<const N: usize>(r: &mut Polyvec<N>, a: &Poly, v: &Polyvec<N>) {
+	for i in 0..N {
+		poly::pointwise_montgomery(&mut r.vec[i], a, &v.vec[i]);
+	}
+}
+
+/// Check infinity norm of polynomials in vector. Assumes input coefficients to be standard
+/// representatives.
+///
+/// Returns 0 if norm of all polynomials are strictly smaller than B and 1 otherwise.
+pub fn chknorm<const N: usize>(v: &Polyvec<N>, bound: i32) -> u8 {
+	for i in 0..N {
+		if poly::chknorm(&v.vec[i], bound) > 0 {
+			return 1;
+		}
+	}
+	0
+}
+
+/// For all coefficients a of polynomials in vector, compute a0, a1 such that a mod Q = a1*2^D + a0
+/// with -2^{D-1} < a0 <= 2^{D-1}. Assumes coefficients to be standard representatives.
+pub fn power2round<const N: usize>(v1: &mut Polyvec<N>, v0: &mut Polyvec<N>) {
+	for i in 0..N {
+		poly::power2round(&mut v1.vec[i], &mut v0.vec[i]);
+	}
+}
+
+/// Decompose all coefficients into high bits (returned in v1) and low bits (returned in v0).
+pub fn decompose<P: ParameterSet, const N: usize>(v1: &mut Polyvec<N>, v0: &mut Polyvec<N>) {
+	for i in 0..N {
+		P::decompose(&mut v1.vec[i], &mut v0.vec[i]);
+	}
+	swap(v1, v0);
+}
+
+/// Returns the number of hint bits set.
+pub fn make_hint<P: ParameterSet, const N: usize>(
+	h: &mut Polyvec<N>,
+	v0: &Polyvec<N>,
+	v1: &Polyvec<N>,
+) -> i32 {
+	let mut s: i32 = 0;
+	for i in 0..N {
+		s += P::make_hint(&mut h.vec[i], &v0.vec[i], &v1.vec[i]);
+	}
+	s
+}
+
+pub fn use_hint<P: ParameterSet, const N: usize>(a: &mut Polyvec<N>, hint: &Polyvec<N>) {
+	for i in 0..N {
+		P::use_hint(&mut a.vec[i], &hint.vec[i]);
+	}
+}
+
+pub fn pack_w1<P: ParameterSet, const K: usize>(r: &mut [u8], a: &Polyvec<K>) {
+	for i in 0..K {
+		P::w1_pack(&mut r[i * P::POLYW1_PACKEDBYTES..], &a.vec[i]);
+	}
+}
@@ -0,0 +1,349 @@
+//! Keys expanded once for repeated signing and verification.
+//!
+//! Verifying against a [PublicKey] expands the matrix A from rho, moves t1 into the NTT domain and
+//! hashes the whole key into tr, every time. [PreparedPublicKey] does that work when it is created,
+//! so each [PreparedPublicKey::verify] only unpacks the signature and recomputes w1. It is worth it
+//! for a key that verifies more than a couple of signatures.
+//!
+//! [PreparedSecretKey] does the same for signing: it keeps A and s1, s2 and t0 in the NTT domain,
+//! and signs to exactly the bytes [SecretKey::sign] produces.
+//!
+//! The expanded state is large (about 57 KB for ML-DSA-87); box it when it is moved around a lot.
+
+use core::fmt;
+
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use crate::{
+	errors::{check_len, Error},
+	ml_dsa::{prehash_mu, pure_mu, ByteArray, ParameterSet, PublicKey, SecretKey, Signature},
+	params,
+	sign::as_mu,
+	CryptoRng, RngCore, PH,
+};
+
+/// A public key with its verification state precomputed.
+#[derive(Clone)]
+pub struct PreparedPublicKey<P: ParameterSet> {
+	public: PublicKey<P>,
+	state: P::PublicKeyState,
+}
+
+impl<P: ParameterSet> PreparedPublicKey<P> {
+	/// Expand `public` for verification.
+	pub fn new(public: PublicKey<P>) -> Self {
+		let state = P::prepare_public_key(public.bytes.as_ref());
+		PreparedPublicKey { public, state }
+	}
+
+	/// The public key this was prepared from.
+	pub fn public_key(&self) -> &PublicKey<P> {
+		&self.public
+	}
+
+	/// Verify a signature for a given message; see [PublicKey::verify].
+	///
+	/// # Arguments
+	///
+	/// * 'msg' - message that is claimed to be signed
+	/// * 'sig' - signature to verify
+	/// * 'ctx' - context string
+	///
+	/// Returns Ok(()) if the verification process was successful, otherwise the reason it failed
+	pub fn verify(&self, msg: &[u8], sig: &[u8], ctx: Option<&[u8]>) -> Result<(), Error> {
+		let mu = pure_mu(P::public_tr(&self.state), ctx, msg)?;
+		self.verify_mu(&mu, sig)
+	}
+
+	/// Verify a HashML-DSA signature for a given message; see [PublicKey::prehash_verify].
+	///
+	/// # Arguments
+	///
+	/// * 'msg' - message that is claimed to be signed
+	/// * 'sig' - signature to verify
+	/// * 'ctx' - context string
+	/// * 'ph' - pre-hash function
+	///
+	/// Returns Ok(()) if the verification process was successful, otherwise the reason it failed
+	pub fn prehash_verify(
+		&self,
+		msg: &[u8],
+		sig: &[u8],
+		ctx: Option<&[u8]>,
+		ph: PH,
+	) -> Result<(), Error> {
+		let mut digest = [0u8; PH::MAX_DIGEST_BYTES];
+		let n = ph.digest(msg, &mut digest);
+		self.verify_prehashed(&digest[..n], sig, ctx, ph)
+	}
+
+	/// Verify a HashML-DSA signature over a digest; see [PublicKey::verify_prehashed].
+	pub fn verify_prehashed(
+		&self,
+		digest: &[u8],
+		sig: &[u8],
+		ctx: Option<&[u8]>,
+		ph: PH,
+	) -> Result<(), Error> {
+		let mu = prehash_mu(P::public_tr(&self.state), ctx, digest, ph)?;
+		self.verify_mu(&mu, sig)
+	}
+
+	/// Verify a signature against a message representative; see [PublicKey::verify_mu].
+	pub fn verify_mu(&self, mu: &[u8], sig: &[u8]) -> Result<(), Error> {
+		check_len(sig.len(), P::SIGNBYTES)?;
+		P::verify_prepared_mu(&self.state, sig, as_mu(mu)?)
+	}
+}
+
+impl<P: ParameterSet> From<PublicKey<P>> for PreparedPublicKey<P> {
+	fn from(public: PublicKey<P>) -> Self {
+		PreparedPublicKey::new(public)
+	}
+}
+
+impl<P: ParameterSet> PartialEq for PreparedPublicKey<P> {
+	fn eq(&self, other: &Self) -> bool {
+		self.public == other.public
+	}
+}
+
+impl<P: ParameterSet> Eq for PreparedPublicKey<P> {}
+
+impl<P: ParameterSet> fmt::Debug for PreparedPublicKey<P> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("PreparedPublicKey")
+			.field("public", &self.public)
+			.finish_non_exhaustive()
+	}
+}
+
+/// A secret key with its signing state precomputed. The secret parts are overwritten with zeroes
+/// when it is dropped.
+#[derive(Clone)]
+pub struct PreparedSecretKey<P: ParameterSet> {
+	state: P::SecretKeyState,
+}
+
+impl<P: ParameterSet> PreparedSecretKey<P> {
+	/// Expand `secret` for signing.
+	pub fn new(secret: &SecretKey<P>) -> Self {
+		PreparedSecretKey { state: P::expand_secret_key(secret.bytes.as_ref()) }
+	}
+
+	/// Compute a signature for a given message; see [SecretKey::sign].
+	///
+	/// # Arguments
+	///
+	/// * 'msg' - message to sign
+	/// * 'ctx' - context string
+	/// * 'hedged' - wether to use RNG or not
+	///
+	/// Returns Signature, or [Error::ContextTooLong] if 'ctx' is longer than 255 bytes
+	pub fn sign(
+		&self,
+		msg: &[u8],
+		ctx: Option<&[u8]>,
+		hedged: bool,
+	) -> Result<Signature<P>, Error> {
+		let mu = pure_mu(P::secret_tr(&self.state), ctx, msg)?;
+		self.sign_mu(&mu, hedged)
+	}
+
+	/// Compute a hedged signature with randomness from `rng`; see [SecretKey::sign_with_rng].
+	pub fn sign_with_rng(
+		&self,
+		msg: &[u8],
+		ctx: Option<&[u8]>,
+		rng: &mut (impl RngCore + CryptoRng),
+	) -> Result<Signature<P>, Error> {
+		let mu = pure_mu(P::secret_tr(&self.state), ctx, msg)?;
+		let mut rnd = [0u8; params::SEEDBYTES];
+		crate::rng_bytes(rng, &mut rnd)?;
+		let mut sig = P::SignatureBytes::zeroed();
+		P::sign_expanded_mu(&self.state, sig.as_mut(), &mu, &rnd);
+		rnd.zeroize();
+		Ok(sig)
+	}
+
+	/// Compute a HashML-DSA signature for a given message; see [SecretKey::prehash_sign].
+	///
+	/// # Arguments
+	///
+	/// * 'msg' - message to sign
+	/// * 'ctx' - context string
+	/// * 'hedged' - wether to use RNG or not
+	/// * 'ph' - pre-hash function
+	///
+	/// Returns Signature, or [Error::ContextTooLong] if 'ctx' is longer than 255 bytes
+	pub fn prehash_sign(
+		&self,
+		msg: &[u8],
+		ctx: Option<&[u8]>,
+		hedged: bool,
+		ph: PH,
+	) -> Result<Signature<P>, Error> {
+		let mut digest = [0u8; PH::MAX_DIGEST_BYTES];
+		let n = ph.digest(msg, &mut digest);
+		self.sign_prehashed(&digest[..n], ctx, hedged, ph)
+	}
+
+	/// Compute a HashML-DSA signature over a digest; see [SecretKey::sign_prehashed].
+	pub fn sign_prehashed(
+		&self,
+		digest: &[u8],
+		ctx: Option<&[u8]>,
+		hedged: bool,
+		ph: PH,
+	) -> Result<Signature<P>, Error> {
+		let mu = prehash_mu(P::secret_tr(&self.state), ctx, digest, ph)?;
+		self.sign_mu(&mu, hedged)
+	}
+
+	/// Compute a signature from a message representative; see [SecretKey::sign_mu].
+	pub fn sign_mu(&self, mu: &[u8], hedged: bool) -> Result<Signature<P>, Error> {
+		let mu = as_mu(mu)?;
+		self.sign_with_rnd(hedged, |sig, rnd| P::sign_expanded_mu(&self.state, sig, mu, rnd))
+	}
+
+	fn sign_with_rnd(
+		&self,
+		hedged: bool,
+		sign: impl FnOnce(&mut [u8], &[u8]),
+	) -> Result<Signature<P>, Error> {
+		let mut rnd = [0u8; params::SEEDBYTES];
+		if hedged {
+			crate::random_bytes(&mut rnd, params::SEEDBYTES)?;
+		}
+		let mut sig = P::SignatureBytes::zeroed();
+		sign(sig.as_mut(), &rnd);
+		rnd.zeroize();
+		Ok(sig)
+	}
+}
+
+impl<P: ParameterSet> From<&SecretKey<P>> for PreparedSecretKey<P> {
+	fn from(secret: &SecretKey<P>) -> Self {
+		PreparedSecretKey::new(secret)
+	}
+}
+
+impl<P: ParameterSet> Zeroize for PreparedSecretKey<P> {
+	fn zeroize(&mut self) {
+		self.state.zeroize();
+	}
+}
+
+// The state wipes itself when it is dropped.
+impl<P: ParameterSet> ZeroizeOnDrop for PreparedSecretKey<P> {}
+
+impl<P: ParameterSet> fmt::Debug for PreparedSecretKey<P> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("PreparedSecretKey")
+			.field("algorithm", &P::ALGORITHM)
+			.finish_non_exhaustive()
+	}
+}
+
+#[cfg(test)]
+#[cfg(feature = "std")]
+mod tests {
+	use super::*;
+	use crate::ml_dsa::{Keypair, MlDsa44, MlDsa65, MlDsa87};
+
+	fn matches_public_key<P: ParameterSet>() {
+		let keys = Keypair::<P>::generate(Some(&[9u8; crate::params::SEEDBYTES])).unwrap();
+		let other = Keypair::<P>::generate(Some(&[8u8; crate::params::SEEDBYTES])).unwrap();
+		let prepared = keys.public.prepare();
+		assert_eq!(prepared.public_key(), &keys.public);
+
+		for i in 0..4u8 {
+			let msg = [i; 33];
+			let sig = keys.sign(&msg, Some(b"ctx"), true).unwrap();
+			assert!(prepared.verify(&msg, sig.as_ref(), Some(b"ctx")).is_ok());
+			assert_eq!(
+				prepared.verify(&msg, sig.as_ref(), None),
+				keys.public.verify(&msg, sig.as_ref(), None)
+			);
+			assert_eq!(
+				prepared.verify(&msg[1..], sig.as_ref(), Some(b"ctx")),
+				Err(Error::ChallengeMismatch)
+			);
+		}
+
+		let sig = other.sign(b"msg", None, false).unwrap();
+		assert_eq!(prepared.verify(b"msg", sig.as_ref(), None), Err(Error::ChallengeMismatch));
+		assert_eq!(
+			prepared.verify(b"msg", &sig.as_ref()[1..], None),
+			keys.public.verify(b"msg", &sig.as_ref()[1..], None)
+		);
+		assert_eq!(
+			prepared.verify(b"msg", sig.as_ref(), Some(&[0; 256])),
+			Err(Error::ContextTooLong)
+		);
+
+		let mu = keys.public.message_representative(b"msg", None).unwrap();
+		let sig = keys.sign_mu(&mu, true).unwrap();
+		assert!(prepared.verify_mu(&mu, sig.as_ref()).is_ok());
+		assert!(prepared.verify(b"msg", sig.as_ref(), None).is_ok());
+		assert_eq!(
+			prepared.verify_mu(&mu[1..], sig.as_ref()),
+			keys.verify_mu(&mu[1..], sig.as_ref())
+		);
+
+		let sig = keys.prehash_sign(b"msg", None, false, crate::PH::SHA256).unwrap();
+		assert!(prepared.prehash_verify(b"msg", sig.as_ref(), None, crate::PH::SHA256).is_ok());
+		assert!(prepared.prehash_verify(b"msg", sig.as_ref(), None, crate::PH::SHA512).is_err());
+	}
+
+	#[test]
+	fn matches_public_key_all_levels() {
+		matches_public_key::<MlDsa44>();
+		matches_public_key::<MlDsa65>();
+		matches_public_key::<MlDsa87>();
+	}
+
+	fn matches_secret_key<P: ParameterSet>() {
+		let keys = Keypair::<P>::generate(Some(&[7u8; crate::params::SEEDBYTES])).unwrap();
+		let prepared = keys.secret.prepare();
+
+		for i in 0..8u8 {
+			let msg = [i; 40];
+			assert_eq!(
+				prepared.sign(&msg, Some(b"ctx"), false).unwrap(),
+				keys.sign(&msg, Some(b"ctx"), false).unwrap()
+			);
+			assert_eq!(
+				prepared.prehash_sign(&msg, None, false, crate::PH::SHA512).unwrap(),
+				keys.prehash_sign(&msg, None, false, crate::PH::SHA512).unwrap()
+			);
+			let sig = prepared.sign(&msg, None, true).unwrap();
+			assert!(keys.verify(&msg, sig.as_ref(), None).is_ok());
+		}
+		let mu = keys.public.message_representative(b"msg", Some(b"ctx")).unwrap();
+		assert_eq!(
+			prepared.sign_mu(&mu, false).unwrap(),
+			keys.sign(b"msg", Some(b"ctx"), false).unwrap()
+		);
+		assert_eq!(prepared.sign(b"msg", Some(&[0; 256]), false), Err(Error::ContextTooLong));
+		assert_eq!(
+			format!("{prepared:?}"),
+			format!("PreparedSecretKey {{ algorithm: {:?}, .. }}", P::ALGORITHM)
+		);
+
+		// Wiping the expanded key leaves nothing to sign with.
+		let mut wiped = prepared.clone();
+		wiped.zeroize();
+		assert_ne!(
+			wiped.sign(b"msg", None, false).unwrap(),
+			prepared.sign(b"msg", None, false).unwrap()
+		);
+	}
+
+	#[test]
+	fn matches_secret_key_all_levels() {
+		matches_secret_key::<MlDsa44>();
+		matches_secret_key::<MlDsa65>();
+		matches_secret_key::<MlDsa87>();
+	}
+}
@@ -0,0 +1,225 @@
+//! Keys stored as the seed they are generated from.
+//!
+//! ML-DSA.KeyGen_internal derives the whole key pair from a 32-byte seed ξ, so storing ξ is
+//! enough to restore it: an ML-DSA-87 secret key takes 4896 bytes, its seed 32. [SeedKeypair]
+//! keeps only the seed and runs [crate::sign::keypair] on it each time a key is needed. Call
+//! [SeedKeypair::expand] once and keep the [Keypair] when signing often.
+
+use core::{fmt, marker::PhantomData};
+
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use crate::{
+	errors::{Error, KeyParsingError},
+	ml_dsa::{ct_eq, Keypair, ParameterSet, PublicKey, SecretKey, Signature},
+	params, CryptoRng, RngCore,
+};
+
+/// A key pair kept as its key generation seed ξ. The seed is overwritten with zeroes when it is
+/// dropped.
+#[derive(Clone)]
+pub struct SeedKeypair<P: ParameterSet> {
+	seed: [u8; params::SEEDBYTES],
+	_marker: PhantomData<P>,
+}
+
+impl<P: ParameterSet> SeedKeypair<P> {
+	/// Wrap a key generation seed.
+	pub fn new(seed: [u8; params::SEEDBYTES]) -> Self {
+		SeedKeypair { seed, _marker: PhantomData }
+	}
+
+	/// Create a SeedKeypair from the params::SEEDBYTES seed bytes.
+	///
+	/// Returns [KeyParsingError::BadSecretKey] if 'bytes' is not params::SEEDBYTES long
+	pub fn from_bytes(bytes: &[u8]) -> Result<Self, KeyParsingError> {
+		match bytes.try_into() {
+			Ok(seed) => Ok(SeedKeypair::new(seed)),
+			Err(_) => Err(KeyParsingError::BadSecretKey),
+		}
+	}
+
+	/// Returns a copy of the seed. The copy is not wiped on drop.
+	pub fn to_bytes(&self) -> [u8; params::SEEDBYTES] {
+		self.seed
+	}
+
+	/// Draw a new seed from the system RNG.
+	///
+	/// Returns [Error::MissingRng] if no RNG is available
+	pub fn generate() -> Result<Self, Error> {
+		let mut seed = [0u8; params::SEEDBYTES];
+		crate::random_bytes(&mut seed, params::SEEDBYTES)?;
+		Ok(SeedKeypair::new(seed))
+	}
+
+	/// Draw a new seed from `rng`.
+	///
+	/// Returns [Error::MissingRng] if 'rng' fails
+	pub fn generate_with_rng(rng: &mut (impl RngCore + CryptoRng)) -> Result<Self, Error> {
+		let mut seed = [0u8; params::SEEDBYTES];
+		crate::rng_bytes(rng, &mut seed)?;
+		Ok(SeedKeypair::new(seed))
+	}
+
+	/// Pair `seed` with the expanded keys it is claimed to generate, e.g. when moving stored
+	/// keys to the seed format.
+	///
+	/// Returns [KeyParsingError::BadSecretKey] if 'seed' is not params::SEEDBYTES long, and
+	/// [KeyParsingError::SeedMismatch] if 'seed' does not generate 'keys'
+	pub fn from_keypair(seed: &[u8], keys: &Keypair<P>) -> Result<Self, KeyParsingError> {
+		let seed = SeedKeypair::from_bytes(seed)?;
+		if !seed.matches(keys) {
+			return Err(KeyParsingError::SeedMismatch);
+		}
+		Ok(seed)
+	}
+
+	/// Whether this seed generates exactly `keys`. The secret keys are compared in constant
+	/// time.
+	pub fn matches(&self, keys: &Keypair<P>) -> bool {
+		let expanded = self.expand();
+		let secret = ct_eq(expanded.secret.bytes.as_ref(), keys.secret.bytes.as_ref());
+		secret & (expanded.public == keys.public)
+	}
+
+	/// Run key generation on the seed; see [Keypair::from_seed].
+	pub fn expand(&self) -> Keypair<P> {
+		Keypair::from_seed(&self.seed)
+	}
+
+	/// The expanded secret key; see [SecretKey::from_seed].
+	pub fn secret_key(&self) -> SecretKey<P> {
+		SecretKey::from_seed(&self.seed)
+	}
+
+	/// The public key, which takes a full key generation to compute.
+	pub fn public_key(&self) -> PublicKey<P> {
+		self.expand().public
+	}
+
+	/// Compute a signature for a given message with the expanded secret key, which is wiped
+	/// again before returning; see [SecretKey::sign].
+	pub fn sign(
+		&self,
+		msg: &[u8],
+		ctx: Option<&[u8]>,
+		hedged: bool,
+	) -> Result<Signature<P>, Error> {
+		self.secret_key().sign(msg, ctx, hedged)
+	}
+
+	/// Compute a hedged signature with randomness from `rng`; see [SecretKey::sign_with_rng].
+	pub fn sign_with_rng(
+		&self,
+		msg: &[u8],
+		ctx: Option<&[u8]>,
+		rng: &mut (impl RngCore + CryptoRng),
+	) -> Result<Signature<P>, Error> {
+		self.secret_key().sign_with_rng(msg, ctx, rng)
+	}
+}
+
+impl<P: ParameterSet> From<&SeedKeypair<P>> for Keypair<P> {
+	fn from(seed: &SeedKeypair<P>) -> Self {
+		seed.expand()
+	}
+}
+
+impl<P: ParameterSet> Zeroize for SeedKeypair<P> {
+	fn zeroize(&mut self) {
+		self.seed.zeroize();
+	}
+}
+
+impl<P: ParameterSet> Drop for SeedKeypair<P> {
+	fn drop(&mut self) {
+		self.zeroize();
+	}
+}
+
+impl<P: ParameterSet> ZeroizeOnDrop for SeedKeypair<P> {}
+
+impl<P: ParameterSet> fmt::Debug for SeedKeypair<P> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("SeedKeypair")
+			.field("algorithm", &P::ALGORITHM)
+			.finish_non_exhaustive()
+	}
+}
+
+#[cfg(test)]
+#[cfg(feature = "std")]
+mod tests {
+	use super::*;
+	use crate::ml_dsa::{MlDsa44, MlDsa65, MlDsa87};
+
+	fn seed_and_expanded_forms_agree<P: ParameterSet>() {
+		let seed = SeedKeypair::<P>::new([4u8; params::SEEDBYTES]);
+		let keys = Keypair::<P>::generate(Some(&seed.to_bytes())).unwrap();
+		let expanded = seed.expand();
+		assert_eq!(expanded.to_bytes(), keys.to_bytes());
+		assert_eq!(Keypair::from(&seed).to_bytes(), keys.to_bytes());
+		assert_eq!(seed.secret_key().bytes, keys.secret.bytes);
+		assert_eq!(seed.public_key(), keys.public);
+		assert!(seed.matches(&keys));
+
+		let sig = seed.sign(b"msg", Some(b"ctx"), false).unwrap();
+		assert_eq!(sig, keys.sign(b"msg", Some(b"ctx"), false).unwrap());
+		assert!(keys.verify(b"msg", sig.as_ref(), Some(b"ctx")).is_ok());
+
+		let restored = SeedKeypair::<P>::from_keypair(&seed.to_bytes(), &keys).unwrap();
+		assert_eq!(restored.to_bytes(), seed.to_bytes());
+
+		// A different seed, a tampered secret key or a wrong length are all rejected.
+		let other = Keypair::<P>::generate(Some(&[5u8; params::SEEDBYTES])).unwrap();
+		assert!(!seed.matches(&other));
+		assert!(matches!(
+			SeedKeypair::<P>::from_keypair(&seed.to_bytes(), &other),
+			Err(KeyParsingError::SeedMismatch)
+		));
+		let mut tampered = keys.clone();
+		tampered.secret.bytes.as_mut()[P::SECRETKEYBYTES - 1] ^= 1;
+		assert!(!seed.matches(&tampered));
+		assert!(matches!(
+			SeedKeypair::<P>::from_keypair(&[4u8; 31], &keys),
+			Err(KeyParsingError::BadSecretKey)
+		));
+	}
+
+	#[test]
+	fn seed_and_expanded_forms_agree_all_levels() {
+		seed_and_expanded_forms_agree::<MlDsa44>();
+		seed_and_expanded_forms_agree::<MlDsa65>();
+		seed_and_expanded_forms_agree::<MlDsa87>();
+	}
+
+	#[test]
+	fn levels_expand_the_same_seed_differently() {
+		let seed = [6u8; params::SEEDBYTES];
+		let pk44 = SeedKeypair::<MlDsa44>::new(seed).public_key();
+		let pk87 = SeedKeypair::<MlDsa87>::new(seed).public_key();
+		assert_ne!(pk44.bytes[..], pk87.bytes[..pk44.bytes.len()]);
+	}
+
+	#[test]
+	fn generated_seeds_are_usable() {
+		use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+
+		let seed =
+			SeedKeypair::<MlDsa65>::generate_with_rng(&mut ChaCha20Rng::seed_from_u64(3)).unwrap();
+		let again =
+			SeedKeypair::<MlDsa65>::generate_with_rng(&mut ChaCha20Rng::seed_from_u64(3)).unwrap();
+		assert_eq!(seed.to_bytes(), again.to_bytes());
+		let sig = seed.sign_with_rng(b"msg", None, &mut ChaCha20Rng::seed_from_u64(4)).unwrap();
+		assert!(seed.public_key().verify(b"msg", &sig, None).is_ok());
+
+		let random = SeedKeypair::<MlDsa65>::generate().unwrap();
+		assert_ne!(random.to_bytes(), seed.to_bytes());
+		assert_eq!(format!("{random:?}"), "SeedKeypair { algorithm: MlDsa65, .. }");
+		assert!(matches!(
+			SeedKeypair::<MlDsa65>::from_bytes(&[0u8; 33]),
+			Err(KeyParsingError::BadSecretKey)
+		));
+	}
+}
@@ -1,6 +1,844 @@
+use zeroize::Zeroize;
+
+use crate::{
+	errors::{check_len, Error},
+	fips202,
+	ml_dsa::{
+		sealed::{PublicKeyState, SecretKeyState},
+		ByteArray, ParameterSet,
+	},
+	packing, params,
+	poly::{self, Poly},
+	polyvec::{self, Polyvec},
+};
+
 pub mod lvl2;
 pub mod lvl3;
 pub mod lvl5;
 pub mod ml_dsa_44;
 pub mod ml_dsa_65;
 pub mod ml_dsa_87;
+
+/// Generate public and private key as specified by FIPS 204 (ML-DSA.KeyGen_internal).
+///
+/// # Arguments
+///
+/// * 'pk' - preallocated buffer for public key
+/// * 'sk' - preallocated buffer for private key
+/// * 'seed' - optional seed of at least params::SEEDBYTES; if None [random_bytes()] is used for
+///   randomness generation
+///
+/// Returns [Error::BadLength] for wrongly sized buffers or a short seed, and [Error::MissingRng]
+/// if no seed is given and no RNG is available.
+pub fn keypair<P: ParameterSet>(
+	pk: &mut [u8],
+	sk: &mut [u8],
+	seed: Option<&[u8]>,
+) -> Result<(), Error> {
+	generate::<P>(pk, sk, seed, true)
+}
+
+/// Generate public and private key using the pre-standard (draft) seed expansion, which omits the
+/// K and L domain separation bytes. Kept so that keys derived with earlier releases can be
+/// regenerated; new keys should use [keypair].
+///
+/// # Arguments
+///
+/// * 'pk' - preallocated buffer for public key
+/// * 'sk' - preallocated buffer for private key
+/// * 'seed' - optional seed of at least params::SEEDBYTES; if None [random_bytes()] is used for
+///   randomness generation
+pub fn keypair_legacy<P: ParameterSet>(
+	pk: &mut [u8],
+	sk: &mut [u8],
+	seed: Option<&[u8]>,
+) -> Result<(), Error> {
+	generate::<P>(pk, sk, seed, false)
+}
+
+/// Shared body of [keypair] and [keypair_legacy].
+pub(crate) fn generate<P: ParameterSet>(
+	pk: &mut [u8],
+	sk: &mut [u8],
+	seed: Option<&[u8]>,
+	domain_separated: bool,
+) -> Result<(), Error> {
+	check_len(pk.len(), P::PUBLICKEYBYTES)?;
+	check_len(sk.len(), P::SECRETKEYBYTES)?;
+	let mut init_seed = [0u8; params::SEEDBYTES];
+	match seed {
+		Some(x) if x.len() >= params::SEEDBYTES =>
+			init_seed.copy_from_slice(&x[..params::SEEDBYTES]),
+		Some(x) => return Err(Error::BadLength { expected: params::SEEDBYTES, actual: x.len() }),
+		None => crate::random_bytes(&mut init_seed, params::SEEDBYTES)?,
+	}
+	P::expand_keypair(pk, sk, &init_seed, domain_separated);
+	init_seed.zeroize();
+	Ok(())
+}
+
+/// Compute a signature for a given message from a private (secret) key.
+///
+/// # Arguments
+///
+/// * 'sig' - preallocated with at least SIGNBYTES buffer
+/// * 'msg' - message to sign
+/// * 'sk' - private key to use
+/// * 'hedged' - indicates wether to randomize the signature or to act deterministicly
+///
+/// Returns [Error::MissingRng] if hedged signing is requested and no RNG is available.
+pub fn signature<P: ParameterSet>(
+	sig: &mut [u8],
+	msg: &[u8],
+	sk: &[u8],
+	hedged: bool,
+) -> Result<(), Error> {
+	let mut rnd = [0u8; params::SEEDBYTES];
+	if hedged {
+		crate::random_bytes(&mut rnd, params::SEEDBYTES)?;
+	}
+	let result = signature_with_rnd::<P>(sig, msg, sk, &rnd);
+	rnd.zeroize();
+	result
+}
+
+/// Compute a signature for a given message from a private (secret) key using caller supplied
+/// randomness; see [sign_internal].
+///
+/// # Arguments
+///
+/// * 'sig' - preallocated with at least SIGNBYTES buffer
+/// * 'msg' - message to sign
+/// * 'sk' - private key to use
+/// * 'rnd' - params::SEEDBYTES of randomness; all zeroes gives the deterministic variant
+pub fn signature_with_rnd<P: ParameterSet>(
+	sig: &mut [u8],
+	msg: &[u8],
+	sk: &[u8],
+	rnd: &[u8],
+) -> Result<(), Error> {
+	check_len(rnd.len(), params::SEEDBYTES)?;
+	sign_internal::<P>(sig, msg, sk, rnd.try_into().unwrap())
+}
+
+/// FIPS 204 ML-DSA.Sign_internal: sign the formatted message M' with caller supplied randomness.
+///
+/// M' is signed as given, so the caller applies any domain separation and context itself; for the
+/// external ML-DSA.Sign interface it is 0 || len(ctx) || ctx || msg. Reusing the 'rnd' of a hedged
+/// signature reproduces that signature exactly.
+///
+/// # Arguments
+///
+/// * 'sig' - preallocated with at least SIGNBYTES buffer
+/// * 'm_prime' - formatted message M'
+/// * 'sk' - private key to use
+/// * 'rnd' - randomness; all zeroes gives the deterministic variant
+pub fn sign_internal<P: ParameterSet>(
+	sig: &mut [u8],
+	m_prime: &[u8],
+	sk: &[u8],
+	rnd: &[u8; params::SEEDBYTES],
+) -> Result<(), Error> {
+	if sig.len() < P::SIGNBYTES {
+		return Err(Error::BadLength { expected: P::SIGNBYTES, actual: sig.len() });
+	}
+	check_len(sk.len(), P::SECRETKEYBYTES)?;
+	P::sign_expanded(&P::expand_secret_key(sk), sig, m_prime, rnd);
+	Ok(())
+}
+
+/// Compute the signature [signature] gives without expanding the secret key, for small thread
+/// stacks and embedded devices. The matrix A and the NTT forms of s1, s2 and t0 are regenerated as
+/// they are used instead of being held, which cuts the peak stack of an optimised ML-DSA-87 build
+/// from about 170 KiB to about 35 KiB at the cost of slower signing.
+///
+/// # Arguments
+///
+/// * 'sig' - preallocated with at least SIGNBYTES buffer
+/// * 'msg' - message to sign
+/// * 'sk' - private key to use
+/// * 'hedged' - indicates wether to randomize the signature or to act deterministicly
+///
+/// Returns [Error::MissingRng] if hedged signing is requested and no RNG is available.
+pub fn signature_low_memory<P: ParameterSet>(
+	sig: &mut [u8],
+	msg: &[u8],
+	sk: &[u8],
+	hedged: bool,
+) -> Result<(), Error> {
+	let mut rnd = [0u8; params::SEEDBYTES];
+	if hedged {
+		crate::random_bytes(&mut rnd, params::SEEDBYTES)?;
+	}
+	let result = signature_low_memory_with_rnd::<P>(sig, msg, sk, &rnd);
+	rnd.zeroize();
+	result
+}
+
+/// [signature_low_memory] with caller supplied randomness; gives the signature of
+/// [signature_with_rnd].
+///
+/// # Arguments
+///
+/// * 'sig' - preallocated with at least SIGNBYTES buffer
+/// * 'msg' - message to sign
+/// * 'sk' - private key to use
+/// * 'rnd' - params::SEEDBYTES of randomness; all zeroes gives the deterministic variant
+pub fn signature_low_memory_with_rnd<P: ParameterSet>(
+	sig: &mut [u8],
+	msg: &[u8],
+	sk: &[u8],
+	rnd: &[u8],
+) -> Result<(), Error> {
+	if sig.len() < P::SIGNBYTES {
+		return Err(Error::BadLength { expected: P::SIGNBYTES, actual: sig.len() });
+	}
+	check_len(sk.len(), P::SECRETKEYBYTES)?;
+	check_len(rnd.len(), params::SEEDBYTES)?;
+	let tr = &sk[2 * params::SEEDBYTES..2 * params::SEEDBYTES + params::TR_BYTES];
+	let mut mu = [0u8; params::CRHBYTES];
+	compute_mu(&mut mu, tr, msg);
+	P::sign_low_memory_mu(sk, sig, &mu, rnd);
+	Ok(())
+}
+
+/// Verify a signature for a given message with a public key.
+///
+/// # Arguments
+///
+/// * 'sig' - signature to verify
+/// * 'm' - message that is claimed to be signed
+/// * 'pk' - public key
+///
+/// Returns Ok(()) if the verification process was successful, otherwise the reason it failed
+pub fn verify<P: ParameterSet>(sig: &[u8], m: &[u8], pk: &[u8]) -> Result<(), Error> {
+	verify_internal::<P>(sig, m, pk)
+}
+
+/// FIPS 204 ML-DSA.Verify_internal: verify a signature over the formatted message M'; see
+/// [sign_internal].
+///
+/// # Arguments
+///
+/// * 'sig' - signature to verify
+/// * 'm_prime' - formatted message M' that is claimed to be signed
+/// * 'pk' - public key
+///
+/// Returns Ok(()) if the verification process was successful, otherwise the reason it failed
+pub fn verify_internal<P: ParameterSet>(
+	sig: &[u8],
+	m_prime: &[u8],
+	pk: &[u8],
+) -> Result<(), Error> {
+	check_len(sig.len(), P::SIGNBYTES)?;
+	check_len(pk.len(), P::PUBLICKEYBYTES)?;
+	P::verify_prepared(&P::prepare_public_key(pk), sig, m_prime)
+}
+
+/// Decode a signature strictly without verifying it against a key: the length must be exactly
+/// SIGNBYTES, the hint must be in its one canonical encoding and z within the norm bound. Every
+/// signature [verify] accepts passes, and one that fails says which check it failed, so malformed
+/// or re-encoded signatures can be turned away before any key is looked up.
+///
+/// # Arguments
+///
+/// * 'sig' - signature to check
+///
+/// Returns Ok(()) if the signature is well formed, otherwise the reason it is not
+pub fn check_signature<P: ParameterSet>(sig: &[u8]) -> Result<(), Error> {
+	check_len(sig.len(), P::SIGNBYTES)?;
+	P::check_signature_encoding(sig)
+}
+
+/// Compute the message representative mu = H(H(pk) || M') for the external-mu interface, the same
+/// way [signature] and [verify] do internally. M' is hashed as given: for ML-DSA it must already
+/// be 0 || len(ctx) || ctx || msg, and for HashML-DSA 1 || len(ctx) || ctx || OID || PH(msg).
+///
+/// # Arguments
+///
+/// * 'mu' - preallocated buffer of params::CRHBYTES
+/// * 'm_prime' - formatted message M' to be signed
+/// * 'pk' - public key of the signer
+pub fn message_representative<P: ParameterSet>(
+	mu: &mut [u8],
+	m_prime: &[u8],
+	pk: &[u8],
+) -> Result<(), Error> {
+	check_len(pk.len(), P::PUBLICKEYBYTES)?;
+	check_len(mu.len(), params::CRHBYTES)?;
+	let mut tr = [0u8; params::TR_BYTES];
+	fips202::shake256(&mut tr, params::TR_BYTES, pk, P::PUBLICKEYBYTES);
+	let mut representative = [0u8; params::CRHBYTES];
+	compute_mu(&mut representative, &tr, m_prime);
+	mu.copy_from_slice(&representative);
+	Ok(())
+}
+
+/// Compute a signature from a message representative computed by [message_representative].
+///
+/// # Arguments
+///
+/// * 'sig' - preallocated with at least SIGNBYTES buffer
+/// * 'mu' - params::CRHBYTES message representative
+/// * 'sk' - private key to use
+/// * 'hedged' - indicates wether to randomize the signature or to act deterministicly
+pub fn sign_mu<P: ParameterSet>(
+	sig: &mut [u8],
+	mu: &[u8],
+	sk: &[u8],
+	hedged: bool,
+) -> Result<(), Error> {
+	let mut rnd = [0u8; params::SEEDBYTES];
+	if hedged {
+		crate::random_bytes(&mut rnd, params::SEEDBYTES)?;
+	}
+	let result = sign_mu_with_rnd::<P>(sig, mu, sk, &rnd);
+	rnd.zeroize();
+	result
+}
+
+/// Compute a signature from a message representative using caller supplied randomness.
+///
+/// # Arguments
+///
+/// * 'sig' - preallocated with at least SIGNBYTES buffer
+/// * 'mu' - params::CRHBYTES message representative
+/// * 'sk' - private key to use
+/// * 'rnd' - params::SEEDBYTES of randomness; all zeroes gives the deterministic variant
+pub fn sign_mu_with_rnd<P: ParameterSet>(
+	sig: &mut [u8],
+	mu: &[u8],
+	sk: &[u8],
+	rnd: &[u8],
+) -> Result<(), Error> {
+	if sig.len() < P::SIGNBYTES {
+		return Err(Error::BadLength { expected: P::SIGNBYTES, actual: sig.len() });
+	}
+	check_len(sk.len(), P::SECRETKEYBYTES)?;
+	check_len(rnd.len(), params::SEEDBYTES)?;
+	P::sign_expanded_mu(&P::expand_secret_key(sk), sig, as_mu(mu)?, rnd);
+	Ok(())
+}
+
+/// Verify a signature against a message representative computed by [message_representative].
+///
+/// # Arguments
+///
+/// * 'sig' - signature to verify
+/// * 'mu' - params::CRHBYTES message representative
+/// * 'pk' - public key
+///
+/// Returns Ok(()) if the verification process was successful, otherwise the reason it failed
+pub fn verify_mu<P: ParameterSet>(sig: &[u8], mu: &[u8], pk: &[u8]) -> Result<(), Error> {
+	check_len(sig.len(), P::SIGNBYTES)?;
+	check_len(pk.len(), P::PUBLICKEYBYTES)?;
+	P::verify_prepared_mu(&P::prepare_public_key(pk), sig, as_mu(mu)?)
+}
+
+/// View `mu` as a message representative, or [Error::BadLength].
+pub(crate) fn as_mu(mu: &[u8]) -> Result<&[u8; params::CRHBYTES], Error> {
+	mu.try_into()
+		.map_err(|_| Error::BadLength { expected: params::CRHBYTES, actual: mu.len() })
+}
+
+/// Key generation for a parameter set whose vector lengths are K and L. Instantiated once per
+/// level by [ParameterSet], which is what the public functions dispatch through.
+pub(crate) fn expand_keypair<P: ParameterSet, const K: usize, const L: usize>(
+	pk: &mut [u8],
+	sk: &mut [u8],
+	seed: &[u8],
+	domain_separated: bool,
+) {
+	let mut init_seed = [0u8; params::SEEDBYTES + 2];
+	init_seed[..params::SEEDBYTES].copy_from_slice(&seed[..params::SEEDBYTES]);
+	let mut seed_len = params::SEEDBYTES;
+	if domain_separated {
+		init_seed[params::SEEDBYTES] = K as u8;
+		init_seed[params::SEEDBYTES + 1] = L as u8;
+		seed_len += 2;
+	}
+
+	const SEEDBUF_LEN: usize = 2 * params::SEEDBYTES + params::CRHBYTES;
+	let mut seedbuf = [0u8; SEEDBUF_LEN];
+	fips202::shake256(&mut seedbuf, SEEDBUF_LEN, &init_seed, seed_len);
+
+	let mut rho = [0u8; params::SEEDBYTES];
+	rho.copy_from_slice(&seedbuf[..params::SEEDBYTES]);
+
+	let mut rhoprime = [0u8; params::CRHBYTES];
+	rhoprime.copy_from_slice(&seedbuf[params::SEEDBYTES..params::SEEDBYTES + params::CRHBYTES]);
+
+	let mut key = [0u8; params::SEEDBYTES];
+	key.copy_from_slice(&seedbuf[params::SEEDBYTES + params::CRHBYTES..]);
+
+	let mut mat = [Polyvec::<L>::default(); K];
+	polyvec::matrix_expand(&mut mat, &rho);
+
+	let mut s1 = Polyvec::<L>::default();
+	polyvec::uniform_eta::<P, L>(&mut s1, &rhoprime, 0);
+
+	let mut s2 = Polyvec::<K>::default();
+	polyvec::uniform_eta::<P, K>(&mut s2, &rhoprime, L as u16);
+
+	let mut s1hat = s1;
+	polyvec::ntt(&mut s1hat);
+
+	let mut t1 = Polyvec::<K>::default();
+	polyvec::matrix_pointwise_montgomery(&mut t1, &mat, &s1hat);
+	polyvec::reduce(&mut t1);
+	polyvec::invntt_tomont(&mut t1);
+	polyvec::add(&mut t1, &s2);
+	polyvec::caddq(&mut t1);
+
+	let mut t0 = Polyvec::<K>::default();
+	polyvec::power2round(&mut t1, &mut t0);
+
+	packing::pack_pk(pk, &rho, &t1);
+
+	let mut tr = [0u8; params::TR_BYTES];
+	fips202::shake256(&mut tr, params::TR_BYTES, pk, P::PUBLICKEYBYTES);
+
+	packing::pack_sk::<P, K, L>(sk, &rho, &tr, &key, &t0, &s1, &s2);
+
+	// Only rho, t1 and tr are public.
+	init_seed.zeroize();
+	seedbuf.zeroize();
+	rhoprime.zeroize();
+	key.zeroize();
+	s1.zeroize();
+	s1hat.zeroize();
+	s2.zeroize();
+	t0.zeroize();
+}
+
+/// Recompute into `pk` the public key of the encoded secret key `sk`, and check that `sk` is one
+/// key generation can produce: s1 and s2 within [-ETA, ETA], and t0 and tr equal to what rho, s1
+/// and s2 give. K cannot be checked. Returns false for an inconsistent key, in which case `pk`
+/// is not meaningful. Assumes sk and pk have the lengths of the parameter set.
+pub(crate) fn public_from_secret<P: ParameterSet, const K: usize, const L: usize>(
+	pk: &mut [u8],
+	sk: &[u8],
+) -> bool {
+	let mut rho = [0u8; params::SEEDBYTES];
+	let mut tr = [0u8; params::TR_BYTES];
+	let mut key = [0u8; params::SEEDBYTES];
+	let mut t0 = Polyvec::<K>::default();
+	let mut s1 = Polyvec::<L>::default();
+	let mut s2 = Polyvec::<K>::default();
+	packing::unpack_sk::<P, K, L>(&mut rho, &mut tr, &mut key, &mut t0, &mut s1, &mut s2, sk);
+	let in_range = polyvec::chknorm(&s1, P::ETA as i32 + 1) == 0 &&
+		polyvec::chknorm(&s2, P::ETA as i32 + 1) == 0;
+
+	// As in expand_keypair: t = As1 + s2 = t1 * 2^d + t0.
+	let mut mat = [Polyvec::<L>::default(); K];
+	polyvec::matrix_expand(&mut mat, &rho);
+	let mut s1hat = s1;
+	polyvec::ntt(&mut s1hat);
+	let mut t1 = Polyvec::<K>::default();
+	polyvec::matrix_pointwise_montgomery(&mut t1, &mat, &s1hat);
+	polyvec::reduce(&mut t1);
+	polyvec::invntt_tomont(&mut t1);
+	polyvec::add(&mut t1, &s2);
+	polyvec::caddq(&mut t1);
+	polyvec::power2round(&mut t1, &mut t0);
+	packing::pack_pk(pk, &rho, &t1);
+	fips202::shake256(&mut tr, params::TR_BYTES, pk, P::PUBLICKEYBYTES);
+
+	// Encoding the key again with the recomputed t0 and tr must give back sk.
+	let mut expected = P::SecretKeyBytes::zeroed();
+	packing::pack_sk::<P, K, L>(expected.as_mut(), &rho, &tr, &key, &t0, &s1, &s2);
+	let consistent = crate::ml_dsa::ct_eq(expected.as_ref(), sk);
+
+	key.zeroize();
+	t0.zeroize();
+	s1.zeroize();
+	s1hat.zeroize();
+	s2.zeroize();
+	expected.zeroize();
+	in_range & consistent
+}
+
+/// The part of signing that depends only on the secret key: unpack it, expand A and move s1, s2
+/// and t0 into the NTT domain. Assumes sk has the length of the parameter set.
+pub(crate) fn expand_secret_key<P: ParameterSet, const K: usize, const L: usize>(
+	sk: &[u8],
+) -> SecretKeyState<K, L> {
+	let mut state = SecretKeyState {
+		mat: [Polyvec::<L>::default(); K],
+		s1: Polyvec::<L>::default(),
+		s2: Polyvec::<K>::default(),
+		t0: Polyvec::<K>::default(),
+		key: [0u8; params::SEEDBYTES],
+		tr: [0u8; params::TR_BYTES],
+	};
+	let mut rho = [0u8; params::SEEDBYTES];
+	packing::unpack_sk::<P, K, L>(
+		&mut rho,
+		&mut state.tr,
+		&mut state.key,
+		&mut state.t0,
+		&mut state.s1,
+		&mut state.s2,
+		sk,
+	);
+	polyvec::matrix_expand(&mut state.mat, &rho);
+	polyvec::ntt(&mut state.s1);
+	polyvec::ntt(&mut state.s2);
+	polyvec::ntt(&mut state.t0);
+	state
+}
+
+/// ML-DSA.Sign_internal with a secret key expanded by [expand_secret_key].
+pub(crate) fn sign_expanded<P: ParameterSet, const K: usize, const L: usize>(
+	sk: &SecretKeyState<K, L>,
+	sig: &mut [u8],
+	msg: &[u8],
+	rnd: &[u8],
+) {
+	let mut mu = [0u8; params::CRHBYTES];
+	compute_mu(&mut mu, &sk.tr, msg);
+	sign_expanded_mu::<P, K, L>(sk, sig, &mu, rnd);
+	mu.zeroize();
+}
+
+/// ML-DSA.Sign_internal from the message representative mu onwards.
+pub(crate) fn sign_expanded_mu<P: ParameterSet, const K: usize, const L: usize>(
+	sk: &SecretKeyState<K, L>,
+	sig: &mut [u8],
+	mu: &[u8; params::CRHBYTES],
+	rnd: &[u8],
+) {
+	let mut state = fips202::KeccakState::default();
+	fips202::shake256_absorb(&mut state, &sk.key, params::SEEDBYTES);
+	fips202::shake256_absorb(&mut state, rnd, params::SEEDBYTES);
+	fips202::shake256_absorb(&mut state, mu, params::CRHBYTES);
+	fips202::shake256_finalize(&mut state);
+	let mut rhoprime = [0u8; params::CRHBYTES];
+	fips202::shake256_squeeze(&mut rhoprime, params::CRHBYTES, &mut state);
+
+	let mut nonce: u16 = 0;
+	let mut y = Polyvec::<L>::default();
+	let mut z: Polyvec<L>;
+	let mut w1 = Polyvec::<K>::default();
+	let mut w0 = Polyvec::<K>::default();
+	let mut cp = Poly::default();
+	let mut h = Polyvec::<K>::default();
+	loop {
+		polyvec::uniform_gamma1::<P, L>(&mut y, &rhoprime, nonce);
+		nonce += 1;
+
+		z = y;
+		polyvec::ntt(&mut z);
+		polyvec::matrix_pointwise_montgomery(&mut w1, &sk.mat, &z);
+		polyvec::reduce(&mut w1);
+		polyvec::invntt_tomont(&mut w1);
+		polyvec::caddq(&mut w1);
+
+		polyvec::decompose::<P, K>(&mut w1, &mut w0);
+		polyvec::pack_w1::<P, K>(sig, &w1);
+
+		state.init();
+		fips202::shake256_absorb(&mut state, mu, params::CRHBYTES);
+		fips202::shake256_absorb(&mut state, sig, K * P::POLYW1_PACKEDBYTES);
+		fips202::shake256_finalize(&mut state);
+		fips202::shake256_squeeze(sig, P::C_DASH_BYTES, &mut state);
+
+		P::challenge(&mut cp, sig);
+		poly::ntt(&mut cp);
+
+		polyvec::pointwise_poly_montgomery(&mut z, &cp, &sk.s1);
+		polyvec::invntt_tomont(&mut z);
+		polyvec::add(&mut z, &y);
+		polyvec::reduce(&mut z);
+
+		if polyvec::chknorm(&z, (P::GAMMA1 - P::BETA) as i32) > 0 {
+			continue;
+		}
+
+		polyvec::pointwise_poly_montgomery(&mut h, &cp, &sk.s2);
+		polyvec::invntt_tomont(&mut h);
+		polyvec::sub(&mut w0, &h);
+		polyvec::reduce(&mut w0);
+
+		if polyvec::chknorm(&w0, (P::GAMMA2 - P::BETA) as i32) > 0 {
+			continue;
+		}
+
+		polyvec::pointwise_poly_montgomery(&mut h, &cp, &sk.t0);
+		polyvec::invntt_tomont(&mut h);
+		polyvec::reduce(&mut h);
+
+		if polyvec::chknorm(&h, P::GAMMA2 as i32) > 0 {
+			continue;
+		}
+
+		polyvec::add(&mut w0, &h);
+
+		let n = polyvec::make_hint::<P, K>(&mut h, &w0, &w1);
+
+		if n > P::OMEGA as i32 {
+			continue;
+		}
+
+		packing::pack_sig::<P, K, L>(sig, None, &z, &h);
+		break;
+	}
+
+	// The masking vector and the responses of rejected attempts must not outlive the call; the
+	// expanded key is wiped by its owner.
+	rhoprime.zeroize();
+	state.zeroize();
+	y.zeroize();
+	z.zeroize();
+	w0.zeroize();
+	h.zeroize();
+}
+
+/// ML-DSA.Sign_internal from mu onwards, working straight from the packed secret key `sk` instead
+/// of a [SecretKeyState]. A is never held: each entry is regenerated with [poly::uniform] while
+/// w = Ay is accumulated row by row, and s1, s2 and t0 are unpacked and moved into the NTT domain
+/// one polynomial at a time when they are needed. The masking vector y is sampled again for
+/// z = y + cs1 rather than kept, and the hint overwrites w0. This trades a few extra SHAKE and NTT
+/// calls per attempt for a stack of about L + 2K polynomials, and gives the same signature as
+/// [sign_expanded_mu]. Assumes sk has the length of the parameter set.
+pub(crate) fn sign_low_memory_mu<P: ParameterSet, const K: usize, const L: usize>(
+	sk: &[u8],
+	sig: &mut [u8],
+	mu: &[u8; params::CRHBYTES],
+	rnd: &[u8],
+) {
+	let rho = &sk[..params::SEEDBYTES];
+	let key = &sk[params::SEEDBYTES..2 * params::SEEDBYTES];
+	let s1 = &sk[2 * params::SEEDBYTES + params::TR_BYTES..];
+	let s2 = &s1[L * P::POLYETA_PACKEDBYTES..];
+	let t0 = &s2[K * P::POLYETA_PACKEDBYTES..];
+
+	let mut state = fips202::KeccakState::default();
+	fips202::shake256_absorb(&mut state, key, params::SEEDBYTES);
+	fips202::shake256_absorb(&mut state, rnd, params::SEEDBYTES);
+	fips202::shake256_absorb(&mut state, mu, params::CRHBYTES);
+	fips202::shake256_finalize(&mut state);
+	let mut rhoprime = [0u8; params::CRHBYTES];
+	fips202::shake256_squeeze(&mut rhoprime, params::CRHBYTES, &mut state);
+
+	let mut nonce: u16 = 0;
+	let mut z = Polyvec::<L>::default();
+	let mut w1 = Polyvec::<K>::default();
+	let mut w0 = Polyvec::<K>::default();
+	let mut cp = Poly::default();
+	// An entry of A or a secret polynomial, and a product with it.
+	let mut a = Poly::default();
+	let mut t = Poly::default();
+	'attempt: loop {
+		let kappa = nonce;
+		nonce += 1;
+
+		// z holds y in the NTT domain until w is computed.
+		polyvec::uniform_gamma1::<P, L>(&mut z, &rhoprime, kappa);
+		polyvec::ntt(&mut z);
+		for (i, w_i) in w1.vec.iter_mut().enumerate() {
+			for j in 0..L {
+				poly::uniform(&mut a, rho, ((i << 8) + j) as u16);
+				if j == 0 {
+					poly::pointwise_montgomery(w_i, &a, &z.vec[0]);
+				} else {
+					poly::pointwise_montgomery(&mut t, &a, &z.vec[j]);
+					poly::add_ip(w_i, &t);
+				}
+			}
+		}
+		polyvec::reduce(&mut w1);
+		polyvec::invntt_tomont(&mut w1);
+		polyvec::caddq(&mut w1);
+
+		polyvec::decompose::<P, K>(&mut w1, &mut w0);
+		polyvec::pack_w1::<P, K>(sig, &w1);
+
+		state.init();
+		fips202::shake256_absorb(&mut state, mu, params::CRHBYTES);
+		fips202::shake256_absorb(&mut state, sig, K * P::POLYW1_PACKEDBYTES);
+		fips202::shake256_finalize(&mut state);
+		fips202::shake256_squeeze(sig, P::C_DASH_BYTES, &mut state);
+
+		P::challenge(&mut cp, sig);
+		poly::ntt(&mut cp);
+
+		for (j, z_j) in z.vec.iter_mut().enumerate() {
+			P::eta_unpack(&mut a, &s1[j * P::POLYETA_PACKEDBYTES..]);
+			poly::ntt(&mut a);
+			poly::pointwise_montgomery(z_j, &cp, &a);
+			poly::invntt_tomont(z_j);
+			P::uniform_gamma1(&mut t, &rhoprime, L as u16 * kappa + j as u16);
+			poly::add_ip(z_j, &t);
+			poly::reduce(z_j);
+		}
+
+		if polyvec::chknorm(&z, (P::GAMMA1 - P::BETA) as i32) > 0 {
+			continue;
+		}
+
+		for (i, w0_i) in w0.vec.iter_mut().enumerate() {
+			P::eta_unpack(&mut a, &s2[i * P::POLYETA_PACKEDBYTES..]);
+			poly::ntt(&mut a);
+			poly::pointwise_montgomery(&mut t, &cp, &a);
+			poly::invntt_tomont(&mut t);
+			poly::sub_ip(w0_i, &t);
+			poly::reduce(w0_i);
+		}
+
+		if polyvec::chknorm(&w0, (P::GAMMA2 - P::BETA) as i32) > 0 {
+			continue;
+		}
+
+		for (i, w0_i) in w0.vec.iter_mut().enumerate() {
+			poly::t0_unpack(&mut a, &t0[i * params::POLYT0_PACKEDBYTES..]);
+			poly::ntt(&mut a);
+			poly::pointwise_montgomery(&mut t, &cp, &a);
+			poly::invntt_tomont(&mut t);
+			poly::reduce(&mut t);
+			if poly::chknorm(&t, P::GAMMA2 as i32) > 0 {
+				continue 'attempt;
+			}
+			poly::add_ip(w0_i, &t);
+		}
+
+		// The hint replaces w0 polynomial by polynomial.
+		let mut n = 0;
+		for (w0_i, w1_i) in w0.vec.iter_mut().zip(w1.vec.iter()) {
+			n += P::make_hint(&mut t, w0_i, w1_i);
+			*w0_i = t;
+		}
+
+		if n > P::OMEGA as i32 {
+			continue;
+		}
+
+		packing::pack_sig::<P, K, L>(sig, None, &z, &w0);
+		break;
+	}
+
+	rhoprime.zeroize();
+	state.zeroize();
+	z.zeroize();
+	w0.zeroize();
+	a.zeroize();
+	t.zeroize();
+}
+
+/// The part of verification that depends only on the public key: expand A, move t1 * 2^d into
+/// the NTT domain and compute tr = H(pk). Assumes pk has the length of the parameter set.
+pub(crate) fn prepare_public_key<P: ParameterSet, const K: usize, const L: usize>(
+	pk: &[u8],
+) -> PublicKeyState<K, L> {
+	let mut tr = [0u8; params::TR_BYTES];
+	fips202::shake256(&mut tr, params::TR_BYTES, pk, P::PUBLICKEYBYTES);
+	expand_public_key::<K, L>(pk, &tr)
+}
+
+/// As [prepare_public_key] for a caller that has already hashed pk into tr.
+pub(crate) fn expand_public_key<const K: usize, const L: usize>(
+	pk: &[u8],
+	tr: &[u8; params::TR_BYTES],
+) -> PublicKeyState<K, L> {
+	let mut state =
+		PublicKeyState { mat: [Polyvec::<L>::default(); K], t1: Polyvec::<K>::default(), tr: *tr };
+	let mut rho = [0u8; params::SEEDBYTES];
+	packing::unpack_pk(&mut rho, &mut state.t1, pk);
+	polyvec::matrix_expand(&mut state.mat, &rho);
+	polyvec::shiftl(&mut state.t1);
+	polyvec::ntt(&mut state.t1);
+	state
+}
+
+/// ML-DSA.Verify_internal against a public key prepared by [prepare_public_key].
+pub(crate) fn verify_prepared<P: ParameterSet, const K: usize, const L: usize>(
+	pk: &PublicKeyState<K, L>,
+	sig: &[u8],
+	m: &[u8],
+) -> Result<(), Error> {
+	let mut mu = [0u8; params::CRHBYTES];
+	compute_mu(&mut mu, &pk.tr, m);
+	verify_prepared_mu::<P, K, L>(pk, sig, &mu)
+}
+
+/// ML-DSA.Verify_internal from the message representative mu onwards.
+pub(crate) fn verify_prepared_mu<P: ParameterSet, const K: usize, const L: usize>(
+	pk: &PublicKeyState<K, L>,
+	sig: &[u8],
+	mu: &[u8; params::CRHBYTES],
+) -> Result<(), Error> {
+	let mut c = [0u8; params::C_DASH_BYTES_MAX];
+	let mut c2 = [0u8; params::C_DASH_BYTES_MAX];
+	let mut cp = Poly::default();
+	let mut z = Polyvec::<L>::default();
+	let (mut t1, mut w1, mut h) =
+		(Polyvec::<K>::default(), Polyvec::<K>::default(), Polyvec::<K>::default());
+	let mut state = fips202::KeccakState::default(); // shake256_init()
+
+	check_len(sig.len(), P::SIGNBYTES)?;
+	decode_sig::<P, K, L>(&mut c, &mut z, &mut h, sig)?;
+
+	// Matrix-vector multiplication; compute Az - c2^dt1
+	P::challenge(&mut cp, &c);
+
+	polyvec::ntt(&mut z);
+	polyvec::matrix_pointwise_montgomery(&mut w1, &pk.mat, &z);
+
+	poly::ntt(&mut cp);
+	polyvec::pointwise_poly_montgomery(&mut t1, &cp, &pk.t1);
+
+	polyvec::sub(&mut w1, &t1);
+	polyvec::reduce(&mut w1);
+	polyvec::invntt_tomont(&mut w1);
+
+	// Reconstruct w1
+	polyvec::caddq(&mut w1);
+	polyvec::use_hint::<P, K>(&mut w1, &h);
+	let mut buf = [0u8; params::POLYVECK_W1_PACKEDBYTES_MAX];
+	polyvec::pack_w1::<P, K>(&mut buf, &w1);
+
+	// Call random oracle and verify challenge
+	fips202::shake256_absorb(&mut state, mu, params::CRHBYTES);
+	fips202::shake256_absorb(&mut state, &buf, K * P::POLYW1_PACKEDBYTES);
+	fips202::shake256_finalize(&mut state);
+	fips202::shake256_squeeze(&mut c2, P::C_DASH_BYTES, &mut state);
+	// Doesn't require constant time equality check
+	if c[..P::C_DASH_BYTES] != c2[..P::C_DASH_BYTES] {
+		return Err(Error::ChallengeMismatch);
+	}
+	Ok(())
+}
+
+/// Unpack a signature of the right length strictly and check the norm bound on z; the checks of
+/// verification that need no key.
+pub(crate) fn decode_sig<P: ParameterSet, const K: usize, const L: usize>(
+	c: &mut [u8],
+	z: &mut Polyvec<L>,
+	h: &mut Polyvec<K>,
+	sig: &[u8],
+) -> Result<(), Error> {
+	packing::unpack_sig::<P, K, L>(c, z, h, sig)?;
+	if polyvec::chknorm(z, (P::GAMMA1 - P::BETA) as i32) > 0 {
+		return Err(Error::NormBoundExceeded);
+	}
+	Ok(())
+}
+
+/// [decode_sig] without keeping the decoded parts.
+pub(crate) fn check_signature_encoding<P: ParameterSet, const K: usize, const L: usize>(
+	sig: &[u8],
+) -> Result<(), Error> {
+	let mut c = [0u8; params::C_DASH_BYTES_MAX];
+	let mut z = Polyvec::<L>::default();
+	let mut h = Polyvec::<K>::default();
+	decode_sig::<P, K, L>(&mut c, &mut z, &mut h, sig)
+}
+
+/// mu = H(tr || m), the message representative that signing and verification work from.
+pub(crate) fn compute_mu(mu: &mut [u8; params::CRHBYTES], tr: &[u8], m: &[u8]) {
+	let mut state = fips202::KeccakState::default();
+	fips202::shake256_absorb(&mut state, tr, params::TR_BYTES);
+	fips202::shake256_absorb(&mut state, m, m.len());
+	fips202::shake256_finalize(&mut state);
+	fips202::shake256_squeeze(mu, params::CRHBYTES, &mut state);
+}
@@ -1,27 +1,13 @@
-#[cfg(not(feature = "no_std"))]
-use rand::RngCore;
-
 use crate::{
+	errors::Error,
 	fips202, packing, params, poly,
 	poly::Poly,
 	polyvec,
 	polyvec::lvl2::{Polyveck, Polyvecl},
 };
 const K: usize = params::lvl2::K;
-#[cfg(not(feature = "no_std"))]
 const L: usize = params::lvl2::L;
 
-/// Generate random bytes.
-///
-/// # Arguments
-///
-/// * 'bytes' - an array to fill with random data
-/// * 'n' - number of bytes to generate
-#[cfg(not(feature = "no_std"))]
-fn random_bytes(bytes: &mut [u8], n: usize) {
-	rand::prelude::thread_rng().try_fill_bytes(&mut bytes[..n]).unwrap();
-}
-
 /// Generate public and private key.
 ///
 /// # Arguments
@@ -29,15 +15,13 @@ fn random_bytes(bytes: &mut [u8], n: usize) {
 /// * 'pk' - preallocated buffer for public key
 /// * 'sk' - preallocated buffer for private key
 /// * 'seed' - optional seed; if None [random_bytes()] is used for randomness generation
-#[cfg(not(feature = "no_std"))]
-pub fn keypair(pk: &mut [u8], sk: &mut [u8], seed: Option<&[u8]>) {
-	let mut init_seed: Vec<u8>;
+///
+/// Returns [Error::MissingRng] if no seed is given and no RNG is available.
+pub fn keypair(pk: &mut [u8], sk: &mut [u8], seed: Option<&[u8]>) -> Result<(), Error> {
+	let mut init_seed = [0u8; params::SEEDBYTES];
 	match seed {
-		Some(x) => init_seed = x.to_vec(),
-		None => {
-			init_seed = vec![0u8; params::SEEDBYTES];
-			random_bytes(&mut init_seed, params::SEEDBYTES)
-		},
+		Some(x) => init_seed.copy_from_slice(&x[..params::SEEDBYTES]),
+		None => crate::random_bytes(&mut init_seed, params::SEEDBYTES)?,
 	};
 
 	const SEEDBUF_LEN: usize = 2 * params::SEEDBYTES + params::CRHBYTES;
@@ -81,6 +65,7 @@ pub fn keypair(pk: &mut [u8], sk: &mut [u8], seed: Option<&[u8]>) {
 	fips202::shake256(&mut tr, params::SEEDBYTES, pk, params::lvl2::PUBLICKEYBYTES);
 
 	packing::lvl2::pack_sk(sk, &rho, &tr, &key, &t0, &s1, &s2);
+	Ok(())
 }
 
 /// Compute a signature for a given message from a private (secret) key.
@@ -91,8 +76,9 @@ pub fn keypair(pk: &mut [u8], sk: &mut [u8], seed: Option<&[u8]>) {
 /// * 'msg' - message to sign
 /// * 'sk' - private key to use
 /// * 'randomized' - indicates wether to randomize the signature or to act deterministicly
-#[cfg(not(feature = "no_std"))]
-pub fn signature(sig: &mut [u8], msg: &[u8], sk: &[u8], randomized: bool) {
+///
+/// Returns [Error::MissingRng] if a randomized signature is requested and no RNG is available.
+pub fn signature(sig: &mut [u8], msg: &[u8], sk: &[u8], randomized: bool) -> Result<(), Error> {
 	let mut rho = [0u8; params::SEEDBYTES];
 	let mut tr = [0u8; params::SEEDBYTES];
 	let mut keymu = [0u8; params::SEEDBYTES + params::CRHBYTES];
@@ -118,7 +104,7 @@ pub fn signature(sig: &mut [u8], msg: &[u8], sk: &[u8], randomized: bool) {
 
 	let mut rhoprime = [0u8; params::CRHBYTES];
 	if randomized {
-		random_bytes(&mut rhoprime, params::CRHBYTES);
+		crate::random_bytes(&mut rhoprime, params::CRHBYTES)?;
 	} else {
 		fips202::shake256(
 			&mut rhoprime,
@@ -199,7 +185,7 @@ pub fn signature(sig: &mut [u8], msg: &[u8], sk: &[u8], randomized: bool) {
 
 		packing::lvl2::pack_sig(sig, None, &z, &h);
 
-		return;
+		return Ok(());
 	}
 }
 
@@ -282,30 +268,30 @@ pub fn verify(sig: &[u8], m: &[u8], pk: &[u8]) -> bool {
 }
 
 #[cfg(test)]
-#[cfg(not(feature = "no_std"))]
+#[cfg(feature = "std")]
 mod tests {
 	#[test]
 	fn self_verify_randomized() {
 		let mut pk = [0u8; crate::params::lvl2::PUBLICKEYBYTES];
 		let mut sk = [0u8; crate::params::lvl2::SECRETKEYBYTES];
-		super::keypair(&mut pk, &mut sk, None);
+		super::keypair(&mut pk, &mut sk, None).unwrap();
 		const MSG_BYTES: usize = 94;
 		let mut msg = [0u8; MSG_BYTES];
-		super::random_bytes(&mut msg, MSG_BYTES);
+		crate::random_bytes(&mut msg, MSG_BYTES).unwrap();
 		let mut sig = [0u8; crate::params::lvl2::SIGNBYTES];
-		super::signature(&mut sig, &msg, &sk, true);
+		super::signature(&mut sig, &msg, &sk, true).unwrap();
 		assert!(super::verify(&sig, &msg, &pk));
 	}
 	#[test]
 	fn self_verify() {
 		let mut pk = [0u8; crate::params::lvl2::PUBLICKEYBYTES];
 		let mut sk = [0u8; crate::params::lvl2::SECRETKEYBYTES];
-		super::keypair(&mut pk, &mut sk, None);
+		super::keypair(&mut pk, &mut sk, None).unwrap();
 		const MSG_BYTES: usize = 94;
 		let mut msg = [0u8; MSG_BYTES];
-		super::random_bytes(&mut msg, MSG_BYTES);
+		crate::random_bytes(&mut msg, MSG_BYTES).unwrap();
 		let mut sig = [0u8; crate::params::lvl2::SIGNBYTES];
-		super::signature(&mut sig, &msg, &sk, false);
+		super::signature(&mut sig, &msg, &sk, false).unwrap();
 		assert!(super::verify(&sig, &msg, &pk));
 	}
 	#[test]
@@ -317,7 +303,7 @@ mod tests {
 		];
 		let mut pk = [0u8; crate::params::lvl2::PUBLICKEYBYTES];
 		let mut sk = [0u8; crate::params::lvl2::SECRETKEYBYTES];
-		super::keypair(&mut pk, &mut sk, Some(&seed));
+		super::keypair(&mut pk, &mut sk, Some(&seed)).unwrap();
 
 		let test_pk: [u8; crate::params::lvl2::PUBLICKEYBYTES] = [
 			0x1C, 0x0E, 0xE1, 0x11, 0x1B, 0x08, 0x00, 0x3F, 0x28, 0xE6, 0x5E, 0x8B, 0x3B, 0xDE,
@@ -794,7 +780,7 @@ mod tests {
 			0xC3, 0x97, 0x72, 0xDB, 0x5C, 0xE0, 0xB2, 0x36,
 		];
 		let mut sig = [0u8; crate::params::lvl2::SIGNBYTES];
-		super::signature(&mut sig, &msg, &sk, false);
+		super::signature(&mut sig, &msg, &sk, false).unwrap();
 
 		let test_sig: [u8; crate::params::lvl2::SIGNBYTES] = [
 			0xAF, 0x59, 0x20, 0x77, 0x46, 0x03, 0xD2, 0x0E, 0x98, 0xA7, 0x9A, 0xA3, 0xAB, 0xFA,
@@ -1167,7 +1153,7 @@ mod tests {
 			0xD1, 0x9C, 0x1D, 0x55, 0x9A, 0x4B, 0xD8, 0xA4,
 		];
 		let mut sig = [0u8; crate::params::lvl2::SIGNBYTES];
-		super::signature(&mut sig, &msg, &sk, false);
+		super::signature(&mut sig, &msg, &sk, false).unwrap();
 
 		let test_sig: [u8; crate::params::lvl2::SIGNBYTES + 66] = [
 			0xB5, 0xF8, 0x9A, 0xE9, 0x07, 0x73, 0xF4, 0x9F, 0xB0, 0xAE, 0xFA, 0xFA, 0x2E, 0x5A,
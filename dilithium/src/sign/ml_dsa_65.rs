@@ -1,72 +1,22 @@
-use crate::{
-	fips202, packing, params, poly,
-	poly::Poly,
-	polyvec,
-	polyvec::lvl3::{Polyveck, Polyvecl},
-};
-const K: usize = params::ml_dsa_65::K;
-#[cfg(not(feature = "no_std"))]
-const L: usize = params::ml_dsa_65::L;
+//! ML-DSA-65 instantiation of the generic functions in [crate::sign].
 
-/// Generate public and private key.
+use crate::{errors::Error, ml_dsa::MlDsa65};
+
+/// Generate public and private key as specified by FIPS 204 (ML-DSA.KeyGen_internal).
 ///
 /// # Arguments
 ///
 /// * 'pk' - preallocated buffer for public key
 /// * 'sk' - preallocated buffer for private key
 /// * 'seed' - optional seed; if None [random_bytes()] is used for randomness generation
-#[cfg(not(feature = "no_std"))]
-pub fn keypair(pk: &mut [u8], sk: &mut [u8], seed: Option<&[u8]>) {
-	let mut init_seed: Vec<u8>;
-	match seed {
-		Some(x) => init_seed = x.to_vec(),
-		None => {
-			init_seed = vec![0u8; params::SEEDBYTES];
-			crate::random_bytes(&mut init_seed, params::SEEDBYTES)
-		},
-	};
-
-	const SEEDBUF_LEN: usize = 2 * params::SEEDBYTES + params::CRHBYTES;
-	let mut seedbuf = [0u8; SEEDBUF_LEN];
-	fips202::shake256(&mut seedbuf, SEEDBUF_LEN, &init_seed, params::SEEDBYTES);
-
-	let mut rho = [0u8; params::SEEDBYTES];
-	rho.copy_from_slice(&seedbuf[..params::SEEDBYTES]);
-
-	let mut rhoprime = [0u8; params::CRHBYTES];
-	rhoprime.copy_from_slice(&seedbuf[params::SEEDBYTES..params::SEEDBYTES + params::CRHBYTES]);
-
-	let mut key = [0u8; params::SEEDBYTES];
-	key.copy_from_slice(&seedbuf[params::SEEDBYTES + params::CRHBYTES..]);
-
-	let mut mat = [Polyvecl::default(); K];
-	polyvec::lvl3::matrix_expand(&mut mat, &rho);
-
-	let mut s1 = Polyvecl::default();
-	polyvec::lvl3::l_uniform_eta(&mut s1, &rhoprime, 0);
-
-	let mut s2 = Polyveck::default();
-	polyvec::lvl3::k_uniform_eta(&mut s2, &rhoprime, L as u16);
-
-	let mut s1hat = s1;
-	polyvec::lvl3::l_ntt(&mut s1hat);
-
-	let mut t1 = Polyveck::default();
-	polyvec::lvl3::matrix_pointwise_montgomery(&mut t1, &mat, &s1hat);
-	polyvec::lvl3::k_reduce(&mut t1);
-	polyvec::lvl3::k_invntt_tomont(&mut t1);
-	polyvec::lvl3::k_add(&mut t1, &s2);
-	polyvec::lvl3::k_caddq(&mut t1);
-
-	let mut t0 = Polyveck::default();
-	polyvec::lvl3::k_power2round(&mut t1, &mut t0);
-
-	packing::ml_dsa_65::pack_pk(pk, &rho, &t1);
-
-	let mut tr = [0u8; params::TR_BYTES];
-	fips202::shake256(&mut tr, params::TR_BYTES, pk, params::ml_dsa_65::PUBLICKEYBYTES);
+pub fn keypair(pk: &mut [u8], sk: &mut [u8], seed: Option<&[u8]>) -> Result<(), Error> {
+	super::keypair::<MlDsa65>(pk, sk, seed)
+}
 
-	packing::ml_dsa_65::pack_sk(sk, &rho, &tr, &key, &t0, &s1, &s2);
+/// Generate public and private key using the pre-standard (draft) seed expansion; see
+/// [super::keypair_legacy].
+pub fn keypair_legacy(pk: &mut [u8], sk: &mut [u8], seed: Option<&[u8]>) -> Result<(), Error> {
+	super::keypair_legacy::<MlDsa65>(pk, sk, seed)
 }
 
 /// Compute a signature for a given message from a private (secret) key.
@@ -77,124 +27,57 @@ pub fn keypair(pk: &mut [u8], sk: &mut [u8], seed: Option<&[u8]>) {
 /// * 'msg' - message to sign
 /// * 'sk' - private key to use
 /// * 'hedged' - indicates wether to randomize the signature or to act deterministicly
-#[cfg(not(feature = "no_std"))]
-pub fn signature(sig: &mut [u8], msg: &[u8], sk: &[u8], hedged: bool) {
-	let mut rho = [0u8; params::SEEDBYTES];
-	let mut tr = [0u8; params::TR_BYTES];
-	let mut keymu = [0u8; params::SEEDBYTES + params::CRHBYTES];
-	let mut t0 = Polyveck::default();
-	let mut s1 = Polyvecl::default();
-	let mut s2 = Polyveck::default();
-
-	packing::ml_dsa_65::unpack_sk(
-		&mut rho,
-		&mut tr,
-		&mut keymu[..params::SEEDBYTES],
-		&mut t0,
-		&mut s1,
-		&mut s2,
-		sk,
-	);
-
-	let mut state = fips202::KeccakState::default();
-	fips202::shake256_absorb(&mut state, &tr, params::TR_BYTES);
-	fips202::shake256_absorb(&mut state, msg, msg.len());
-	fips202::shake256_finalize(&mut state);
-	fips202::shake256_squeeze(&mut keymu[params::SEEDBYTES..], params::CRHBYTES, &mut state);
-
-	let mut rnd = [0u8; params::SEEDBYTES];
-	if hedged {
-		crate::random_bytes(&mut rnd, params::SEEDBYTES);
-	}
-	state.init();
-	fips202::shake256_absorb(&mut state, &keymu[..params::SEEDBYTES], params::SEEDBYTES);
-	fips202::shake256_absorb(&mut state, &rnd, params::SEEDBYTES);
-	fips202::shake256_absorb(&mut state, &keymu[params::SEEDBYTES..], params::CRHBYTES);
-	fips202::shake256_finalize(&mut state);
-	let mut rhoprime = [0u8; params::CRHBYTES];
-	fips202::shake256_squeeze(&mut rhoprime, params::CRHBYTES, &mut state);
-
-	let mut mat = [Polyvecl::default(); K];
-	polyvec::lvl3::matrix_expand(&mut mat, &rho);
-	polyvec::lvl3::l_ntt(&mut s1);
-	polyvec::lvl3::k_ntt(&mut s2);
-	polyvec::lvl3::k_ntt(&mut t0);
-
-	let mut nonce: u16 = 0;
-	let mut y = Polyvecl::default();
-	let mut w1 = Polyveck::default();
-	let mut w0 = Polyveck::default();
-	let mut cp = Poly::default();
-	let mut h = Polyveck::default();
-	loop {
-		polyvec::lvl3::l_uniform_gamma1(&mut y, &rhoprime, nonce);
-		nonce += 1;
-
-		let mut z = y;
-		polyvec::lvl3::l_ntt(&mut z);
-		polyvec::lvl3::matrix_pointwise_montgomery(&mut w1, &mat, &z);
-		polyvec::lvl3::k_reduce(&mut w1);
-		polyvec::lvl3::k_invntt_tomont(&mut w1);
-		polyvec::lvl3::k_caddq(&mut w1);
-
-		polyvec::lvl3::k_decompose(&mut w1, &mut w0);
-		polyvec::lvl3::k_pack_w1(sig, &w1);
-
-		state.init();
-		fips202::shake256_absorb(&mut state, &keymu[params::SEEDBYTES..], params::CRHBYTES);
-		fips202::shake256_absorb(&mut state, sig, K * params::ml_dsa_65::POLYW1_PACKEDBYTES);
-		fips202::shake256_finalize(&mut state);
-		fips202::shake256_squeeze(sig, params::ml_dsa_65::C_DASH_BYTES, &mut state);
-
-		poly::ml_dsa_65::challenge(&mut cp, sig);
-		poly::ntt(&mut cp);
-
-		polyvec::lvl3::l_pointwise_poly_montgomery(&mut z, &cp, &s1);
-		polyvec::lvl3::l_invntt_tomont(&mut z);
-		polyvec::lvl3::l_add(&mut z, &y);
-		polyvec::lvl3::l_reduce(&mut z);
-
-		if polyvec::lvl3::l_chknorm(
-			&z,
-			(params::ml_dsa_65::GAMMA1 - params::ml_dsa_65::BETA) as i32,
-		) > 0
-		{
-			continue;
-		}
-
-		polyvec::lvl3::k_pointwise_poly_montgomery(&mut h, &cp, &s2);
-		polyvec::lvl3::k_invntt_tomont(&mut h);
-		polyvec::lvl3::k_sub(&mut w0, &h);
-		polyvec::lvl3::k_reduce(&mut w0);
-
-		if polyvec::lvl3::k_chknorm(
-			&w0,
-			(params::ml_dsa_65::GAMMA2 - params::ml_dsa_65::BETA) as i32,
-		) > 0
-		{
-			continue;
-		}
-
-		polyvec::lvl3::k_pointwise_poly_montgomery(&mut h, &cp, &t0);
-		polyvec::lvl3::k_invntt_tomont(&mut h);
-		polyvec::lvl3::k_reduce(&mut h);
-
-		if polyvec::lvl3::k_chknorm(&h, params::ml_dsa_65::GAMMA2 as i32) > 0 {
-			continue;
-		}
-
-		polyvec::lvl3::k_add(&mut w0, &h);
-
-		let n = polyvec::lvl3::k_make_hint(&mut h, &w0, &w1);
+pub fn signature(sig: &mut [u8], msg: &[u8], sk: &[u8], hedged: bool) -> Result<(), Error> {
+	super::signature::<MlDsa65>(sig, msg, sk, hedged)
+}
 
-		if n > params::ml_dsa_65::OMEGA as i32 {
-			continue;
-		}
+/// Compute a signature for a given message from a private (secret) key using caller supplied
+/// randomness; see [sign_internal].
+///
+/// # Arguments
+///
+/// * 'sig' - preallocated with at least SIGNBYTES buffer
+/// * 'msg' - message to sign
+/// * 'sk' - private key to use
+/// * 'rnd' - params::SEEDBYTES of randomness; all zeroes gives the deterministic variant
+pub fn signature_with_rnd(sig: &mut [u8], msg: &[u8], sk: &[u8], rnd: &[u8]) -> Result<(), Error> {
+	super::signature_with_rnd::<MlDsa65>(sig, msg, sk, rnd)
+}
 
-		packing::ml_dsa_65::pack_sig(sig, None, &z, &h);
+/// Compute a signature without expanding the secret key, for small stacks; see
+/// [super::signature_low_memory].
+///
+/// # Arguments
+///
+/// * 'sig' - preallocated with at least SIGNBYTES buffer
+/// * 'msg' - message to sign
+/// * 'sk' - private key to use
+/// * 'hedged' - indicates wether to randomize the signature or to act deterministicly
+pub fn signature_low_memory(
+	sig: &mut [u8],
+	msg: &[u8],
+	sk: &[u8],
+	hedged: bool,
+) -> Result<(), Error> {
+	super::signature_low_memory::<MlDsa65>(sig, msg, sk, hedged)
+}
 
-		return;
-	}
+/// [signature_low_memory] with caller supplied randomness; see
+/// [super::signature_low_memory_with_rnd].
+///
+/// # Arguments
+///
+/// * 'sig' - preallocated with at least SIGNBYTES buffer
+/// * 'msg' - message to sign
+/// * 'sk' - private key to use
+/// * 'rnd' - params::SEEDBYTES of randomness; all zeroes gives the deterministic variant
+pub fn signature_low_memory_with_rnd(
+	sig: &mut [u8],
+	msg: &[u8],
+	sk: &[u8],
+	rnd: &[u8],
+) -> Result<(), Error> {
+	super::signature_low_memory_with_rnd::<MlDsa65>(sig, msg, sk, rnd)
 }
 
 /// Verify a signature for a given message with a public key.
@@ -205,102 +88,266 @@ pub fn signature(sig: &mut [u8], msg: &[u8], sk: &[u8], hedged: bool) {
 /// * 'm' - message that is claimed to be signed
 /// * 'pk' - public key
 ///
-/// Returns 'true' if the verification process was successful, 'false' otherwise
-pub fn verify(sig: &[u8], m: &[u8], pk: &[u8]) -> bool {
-	let mut buf = [0u8; K * crate::params::ml_dsa_65::POLYW1_PACKEDBYTES];
-	let mut rho = [0u8; params::SEEDBYTES];
-	let mut mu = [0u8; params::CRHBYTES];
-	let mut c = [0u8; params::ml_dsa_65::C_DASH_BYTES];
-	let mut c2 = [0u8; params::ml_dsa_65::C_DASH_BYTES];
-	let mut cp = Poly::default();
-	let (mut mat, mut z) = ([Polyvecl::default(); K], Polyvecl::default());
-	let (mut t1, mut w1, mut h) = (Polyveck::default(), Polyveck::default(), Polyveck::default());
-	let mut state = fips202::KeccakState::default(); // shake256_init()
-
-	if sig.len() != crate::params::ml_dsa_65::SIGNBYTES {
-		return false;
-	}
-
-	packing::ml_dsa_65::unpack_pk(&mut rho, &mut t1, pk);
-	if !packing::ml_dsa_65::unpack_sig(&mut c, &mut z, &mut h, sig) {
-		return false;
-	}
-	if polyvec::lvl3::l_chknorm(
-		&z,
-		(crate::params::ml_dsa_65::GAMMA1 - crate::params::ml_dsa_65::BETA) as i32,
-	) > 0
-	{
-		return false;
-	}
+/// Returns Ok(()) if the verification process was successful, otherwise the reason it failed
+pub fn verify(sig: &[u8], m: &[u8], pk: &[u8]) -> Result<(), Error> {
+	super::verify::<MlDsa65>(sig, m, pk)
+}
 
-	// Compute CRH(CRH(rho, t1), msg)
-	fips202::shake256(&mut mu, params::CRHBYTES, pk, crate::params::ml_dsa_65::PUBLICKEYBYTES);
-	fips202::shake256_absorb(&mut state, &mu, params::CRHBYTES);
-	fips202::shake256_absorb(&mut state, m, m.len());
-	fips202::shake256_finalize(&mut state);
-	fips202::shake256_squeeze(&mut mu, params::CRHBYTES, &mut state);
+/// FIPS 204 ML-DSA.Sign_internal over the formatted message M', which is signed as given; see
+/// [super::sign_internal].
+///
+/// # Arguments
+///
+/// * 'sig' - preallocated with at least SIGNBYTES buffer
+/// * 'm_prime' - formatted message M'
+/// * 'sk' - private key to use
+/// * 'rnd' - randomness; all zeroes gives the deterministic variant
+pub fn sign_internal(
+	sig: &mut [u8],
+	m_prime: &[u8],
+	sk: &[u8],
+	rnd: &[u8; crate::params::SEEDBYTES],
+) -> Result<(), Error> {
+	super::sign_internal::<MlDsa65>(sig, m_prime, sk, rnd)
+}
 
-	// Matrix-vector multiplication; compute Az - c2^dt1
-	poly::ml_dsa_65::challenge(&mut cp, &c);
-	polyvec::lvl3::matrix_expand(&mut mat, &rho);
+/// FIPS 204 ML-DSA.Verify_internal over the formatted message M'; see [super::verify_internal].
+///
+/// Returns Ok(()) if the verification process was successful, otherwise the reason it failed
+pub fn verify_internal(sig: &[u8], m_prime: &[u8], pk: &[u8]) -> Result<(), Error> {
+	super::verify_internal::<MlDsa65>(sig, m_prime, pk)
+}
 
-	polyvec::lvl3::l_ntt(&mut z);
-	polyvec::lvl3::matrix_pointwise_montgomery(&mut w1, &mat, &z);
+/// Decode a signature strictly without a key; see [super::check_signature].
+///
+/// Returns Ok(()) if the signature is well formed, otherwise the reason it is not
+pub fn check_signature(sig: &[u8]) -> Result<(), Error> {
+	super::check_signature::<MlDsa65>(sig)
+}
 
-	poly::ntt(&mut cp);
-	polyvec::lvl3::k_shiftl(&mut t1);
-	polyvec::lvl3::k_ntt(&mut t1);
-	let t1_2 = t1;
-	polyvec::lvl3::k_pointwise_poly_montgomery(&mut t1, &cp, &t1_2);
+/// Compute the message representative mu for [sign_mu] and [verify_mu]; see
+/// [super::message_representative].
+///
+/// # Arguments
+///
+/// * 'mu' - preallocated buffer of params::CRHBYTES
+/// * 'm_prime' - formatted message M' to be signed
+/// * 'pk' - public key of the signer
+pub fn message_representative(mu: &mut [u8], m_prime: &[u8], pk: &[u8]) -> Result<(), Error> {
+	super::message_representative::<MlDsa65>(mu, m_prime, pk)
+}
 
-	polyvec::lvl3::k_sub(&mut w1, &t1);
-	polyvec::lvl3::k_reduce(&mut w1);
-	polyvec::lvl3::k_invntt_tomont(&mut w1);
+/// Compute a signature from a message representative; see [super::sign_mu].
+///
+/// # Arguments
+///
+/// * 'sig' - preallocated with at least SIGNBYTES buffer
+/// * 'mu' - params::CRHBYTES message representative
+/// * 'sk' - private key to use
+/// * 'hedged' - indicates wether to randomize the signature or to act deterministicly
+pub fn sign_mu(sig: &mut [u8], mu: &[u8], sk: &[u8], hedged: bool) -> Result<(), Error> {
+	super::sign_mu::<MlDsa65>(sig, mu, sk, hedged)
+}
 
-	// Reconstruct w1
-	polyvec::lvl3::k_caddq(&mut w1);
-	polyvec::lvl3::k_use_hint(&mut w1, &h);
-	polyvec::lvl3::k_pack_w1(&mut buf, &w1);
+/// Compute a signature from a message representative using caller supplied randomness; see
+/// [super::sign_mu_with_rnd].
+///
+/// # Arguments
+///
+/// * 'sig' - preallocated with at least SIGNBYTES buffer
+/// * 'mu' - params::CRHBYTES message representative
+/// * 'sk' - private key to use
+/// * 'rnd' - params::SEEDBYTES of randomness; all zeroes gives the deterministic variant
+pub fn sign_mu_with_rnd(sig: &mut [u8], mu: &[u8], sk: &[u8], rnd: &[u8]) -> Result<(), Error> {
+	super::sign_mu_with_rnd::<MlDsa65>(sig, mu, sk, rnd)
+}
 
-	// Call random oracle and verify challenge
-	state.init();
-	fips202::shake256_absorb(&mut state, &mu, params::CRHBYTES);
-	fips202::shake256_absorb(&mut state, &buf, K * crate::params::ml_dsa_65::POLYW1_PACKEDBYTES);
-	fips202::shake256_finalize(&mut state);
-	fips202::shake256_squeeze(&mut c2, params::ml_dsa_65::C_DASH_BYTES, &mut state);
-	// Doesn't require constant time equality check
-	if c != c2 {
-		return false;
-	}
-	true
+/// Verify a signature against a message representative; see [super::verify_mu].
+///
+/// # Arguments
+///
+/// * 'sig' - signature to verify
+/// * 'mu' - params::CRHBYTES message representative
+/// * 'pk' - public key
+///
+/// Returns Ok(()) if the verification process was successful, otherwise the reason it failed
+pub fn verify_mu(sig: &[u8], mu: &[u8], pk: &[u8]) -> Result<(), Error> {
+	super::verify_mu::<MlDsa65>(sig, mu, pk)
 }
 
 #[cfg(test)]
-#[cfg(not(feature = "no_std"))]
+#[cfg(feature = "std")]
 mod tests {
+	use crate::errors::Error;
+
 	#[test]
 	fn self_verify_hedged() {
 		let mut pk = [0u8; crate::params::ml_dsa_65::PUBLICKEYBYTES];
 		let mut sk = [0u8; crate::params::ml_dsa_65::SECRETKEYBYTES];
-		super::keypair(&mut pk, &mut sk, None);
+		super::keypair(&mut pk, &mut sk, None).unwrap();
 		const MSG_BYTES: usize = 94;
 		let mut msg = [0u8; MSG_BYTES];
-		crate::random_bytes(&mut msg, MSG_BYTES);
+		crate::random_bytes(&mut msg, MSG_BYTES).unwrap();
 		let mut sig = [0u8; crate::params::ml_dsa_65::SIGNBYTES];
-		super::signature(&mut sig, &msg, &sk, true);
-		assert!(super::verify(&sig, &msg, &pk));
+		super::signature(&mut sig, &msg, &sk, true).unwrap();
+		assert!(super::verify(&sig, &msg, &pk).is_ok());
 	}
 	#[test]
 	fn self_verify() {
 		let mut pk = [0u8; crate::params::ml_dsa_65::PUBLICKEYBYTES];
 		let mut sk = [0u8; crate::params::ml_dsa_65::SECRETKEYBYTES];
-		super::keypair(&mut pk, &mut sk, None);
+		super::keypair(&mut pk, &mut sk, None).unwrap();
+		const MSG_BYTES: usize = 94;
+		let mut msg = [0u8; MSG_BYTES];
+		crate::random_bytes(&mut msg, MSG_BYTES).unwrap();
+		let mut sig = [0u8; crate::params::ml_dsa_65::SIGNBYTES];
+		super::signature(&mut sig, &msg, &sk, false).unwrap();
+		assert!(super::verify(&sig, &msg, &pk).is_ok());
+	}
+	#[test]
+	fn external_mu() {
+		let mut pk = [0u8; crate::params::ml_dsa_65::PUBLICKEYBYTES];
+		let mut sk = [0u8; crate::params::ml_dsa_65::SECRETKEYBYTES];
+		super::keypair(&mut pk, &mut sk, Some(&[1u8; crate::params::SEEDBYTES])).unwrap();
+		let msg = [3u8; 94];
+		let mut mu = [0u8; crate::params::CRHBYTES];
+		super::message_representative(&mut mu, &msg, &pk).unwrap();
+
+		let mut sig = [0u8; crate::params::ml_dsa_65::SIGNBYTES];
+		super::sign_mu(&mut sig, &mu, &sk, false).unwrap();
+		let mut expected = [0u8; crate::params::ml_dsa_65::SIGNBYTES];
+		super::signature(&mut expected, &msg, &sk, false).unwrap();
+		assert_eq!(sig, expected);
+		assert!(super::verify_mu(&sig, &mu, &pk).is_ok());
+		super::sign_mu_with_rnd(&mut expected, &mu, &sk, &[0u8; crate::params::SEEDBYTES]).unwrap();
+		assert_eq!(sig, expected);
+
+		super::sign_mu(&mut sig, &mu, &sk, true).unwrap();
+		assert!(super::verify(&sig, &msg, &pk).is_ok());
+		mu[0] ^= 1;
+		assert_eq!(super::verify_mu(&sig, &mu, &pk), Err(Error::ChallengeMismatch));
+		assert_eq!(
+			super::verify_mu(&sig, &mu[1..], &pk),
+			Err(Error::BadLength { expected: crate::params::CRHBYTES, actual: 63 })
+		);
+	}
+	#[test]
+	fn internal_interface() {
+		use rand_chacha::{
+			rand_core::{RngCore, SeedableRng},
+			ChaCha20Rng,
+		};
+
+		let mut pk = [0u8; crate::params::ml_dsa_65::PUBLICKEYBYTES];
+		let mut sk = [0u8; crate::params::ml_dsa_65::SECRETKEYBYTES];
+		super::keypair(&mut pk, &mut sk, Some(&[2u8; crate::params::SEEDBYTES])).unwrap();
+		let keys = crate::ml_dsa::Keypair::<crate::ml_dsa::MlDsa65>::from_bytes(
+			&[&sk[..], &pk[..]].concat(),
+		)
+		.unwrap();
+		let hedged = keys
+			.sign_with_rng(b"msg", Some(b"ctx"), &mut ChaCha20Rng::seed_from_u64(5))
+			.unwrap();
+
+		// Replaying the rnd that was drawn reproduces the hedged signature.
+		let mut rnd = [0u8; crate::params::SEEDBYTES];
+		ChaCha20Rng::seed_from_u64(5).fill_bytes(&mut rnd);
+		let m_prime = [&[0u8, 3][..], b"ctx", b"msg"].concat();
+		let mut sig = [0u8; crate::params::ml_dsa_65::SIGNBYTES];
+		super::sign_internal(&mut sig, &m_prime, &sk, &rnd).unwrap();
+		assert_eq!(hedged.as_ref(), sig);
+		assert!(super::verify_internal(&sig, &m_prime, &pk).is_ok());
+		assert!(keys.verify(b"msg", &sig, Some(b"ctx")).is_ok());
+
+		// M' is not wrapped again.
+		assert!(super::verify_internal(&sig, b"msg", &pk).is_err());
+		super::sign_internal(&mut sig, b"msg", &sk, &[0u8; crate::params::SEEDBYTES]).unwrap();
+		let mut expected = [0u8; crate::params::ml_dsa_65::SIGNBYTES];
+		super::signature(&mut expected, b"msg", &sk, false).unwrap();
+		assert_eq!(sig, expected);
+		assert_eq!(
+			super::verify_internal(&sig[1..], b"msg", &pk),
+			Err(Error::BadLength {
+				expected: crate::params::ml_dsa_65::SIGNBYTES,
+				actual: crate::params::ml_dsa_65::SIGNBYTES - 1
+			})
+		);
+	}
+	#[test]
+	fn low_memory() {
+		let mut pk = [0u8; crate::params::ml_dsa_65::PUBLICKEYBYTES];
+		let mut sk = [0u8; crate::params::ml_dsa_65::SECRETKEYBYTES];
+		let mut sig = [0u8; crate::params::ml_dsa_65::SIGNBYTES];
+		let mut expected = [0u8; crate::params::ml_dsa_65::SIGNBYTES];
+		// Enough keys and rnds that plenty of attempts are rejected along the way.
+		for i in 0..16u8 {
+			super::keypair(&mut pk, &mut sk, Some(&[i; crate::params::SEEDBYTES])).unwrap();
+			let msg = [i; 94];
+			for rnd in [[0u8; crate::params::SEEDBYTES], [i ^ 0x5a; crate::params::SEEDBYTES]] {
+				super::signature_with_rnd(&mut expected, &msg, &sk, &rnd).unwrap();
+				super::signature_low_memory_with_rnd(&mut sig, &msg, &sk, &rnd).unwrap();
+				assert_eq!(sig, expected);
+			}
+		}
+		super::signature_low_memory(&mut sig, b"msg", &sk, true).unwrap();
+		assert!(super::verify(&sig, b"msg", &pk).is_ok());
+		assert_eq!(
+			super::signature_low_memory(&mut sig, b"msg", &sk[1..], false),
+			Err(Error::BadLength {
+				expected: crate::params::ml_dsa_65::SECRETKEYBYTES,
+				actual: crate::params::ml_dsa_65::SECRETKEYBYTES - 1
+			})
+		);
+	}
+	#[test]
+	fn keypair_fips204() {
+		// ACVP-Server ML-DSA-65 keyGen, tcId 26; pk and sk are compared by their SHAKE256 digest
+		let seed: [u8; crate::params::SEEDBYTES] = [
+			0x70, 0xce, 0xfb, 0x9a, 0xed, 0x5b, 0x68, 0xe0, 0x18, 0xb0, 0x79, 0xda, 0x82, 0x84,
+			0xb9, 0xd5, 0xca, 0xd5, 0x49, 0x9e, 0xd9, 0xc2, 0x65, 0xff, 0x73, 0x58, 0x80, 0x05,
+			0xd8, 0x5c, 0x22, 0x5c,
+		];
+		let mut pk = [0u8; crate::params::ml_dsa_65::PUBLICKEYBYTES];
+		let mut sk = [0u8; crate::params::ml_dsa_65::SECRETKEYBYTES];
+		super::keypair(&mut pk, &mut sk, Some(&seed)).unwrap();
+
+		let mut digest = [0u8; 32];
+		crate::fips202::shake256(&mut digest, 32, &pk, crate::params::ml_dsa_65::PUBLICKEYBYTES);
+		assert_eq!(
+			digest,
+			[
+				0x60, 0xc9, 0x13, 0x44, 0xb0, 0xc6, 0x76, 0x4c, 0x20, 0x4e, 0x5b, 0x8d, 0x42, 0x46,
+				0x50, 0xbe, 0xc0, 0x6b, 0x9e, 0x2e, 0x62, 0x5a, 0xf0, 0x7e, 0x23, 0xf4, 0x95, 0x0c,
+				0xa2, 0x4f, 0xb4, 0xd6,
+			]
+		);
+		crate::fips202::shake256(&mut digest, 32, &sk, crate::params::ml_dsa_65::SECRETKEYBYTES);
+		assert_eq!(
+			digest,
+			[
+				0xdb, 0x9a, 0x9a, 0x01, 0x94, 0xf6, 0x75, 0xa1, 0xff, 0x03, 0x66, 0xb6, 0xa1, 0xbc,
+				0xb3, 0xee, 0xf2, 0x5c, 0x80, 0x8f, 0x39, 0x79, 0xbe, 0xd4, 0x3f, 0x16, 0x9b, 0xf6,
+				0x35, 0x83, 0x1f, 0xf7,
+			]
+		);
+	}
+	#[test]
+	fn keypair_legacy() {
+		let seed = [7u8; crate::params::SEEDBYTES];
+		let mut pk = [0u8; crate::params::ml_dsa_65::PUBLICKEYBYTES];
+		let mut sk = [0u8; crate::params::ml_dsa_65::SECRETKEYBYTES];
+		super::keypair(&mut pk, &mut sk, Some(&seed)).unwrap();
+		let mut legacy_pk = [0u8; crate::params::ml_dsa_65::PUBLICKEYBYTES];
+		let mut legacy_sk = [0u8; crate::params::ml_dsa_65::SECRETKEYBYTES];
+		super::keypair_legacy(&mut legacy_pk, &mut legacy_sk, Some(&seed)).unwrap();
+		assert_ne!(pk, legacy_pk);
+
 		const MSG_BYTES: usize = 94;
 		let mut msg = [0u8; MSG_BYTES];
-		crate::random_bytes(&mut msg, MSG_BYTES);
+		crate::random_bytes(&mut msg, MSG_BYTES).unwrap();
 		let mut sig = [0u8; crate::params::ml_dsa_65::SIGNBYTES];
-		super::signature(&mut sig, &msg, &sk, false);
-		assert!(super::verify(&sig, &msg, &pk));
+		super::signature(&mut sig, &msg, &legacy_sk, false).unwrap();
+		assert!(super::verify(&sig, &msg, &legacy_pk).is_ok());
 	}
 	//    #[test]
 	//    fn keypair() {
@@ -0,0 +1,252 @@
+//! Incremental signing and verification of messages that arrive in pieces.
+//!
+//! [Signer] and [Verifier] absorb the M' prefix (domain byte, context length and context) and then
+//! the message chunks straight into the SHAKE256 state that computes mu = H(tr || M'), so the
+//! message is never buffered. The signature is the one [SecretKey::sign] gives for the
+//! concatenated message. Both implement `std::io::Write`, and [Signer::update_reader] and
+//! [Verifier::update_reader] drain an `std::io::Read`.
+
+use core::marker::PhantomData;
+#[cfg(feature = "std")]
+use std::io;
+
+use zeroize::Zeroize;
+
+use crate::{
+	errors::Error,
+	fips202::{self, KeccakState},
+	ml_dsa::{check_ctx, sign_mu_with, ByteArray, ParameterSet, PublicKey, SecretKey, Signature},
+	params, CryptoRng, RngCore,
+};
+
+/// Signs a message fed in chunks; see [SecretKey::signer].
+pub struct Signer<'a, P: ParameterSet> {
+	sk: &'a [u8],
+	state: KeccakState,
+	_marker: PhantomData<P>,
+}
+
+impl<'a, P: ParameterSet> Signer<'a, P> {
+	/// Start signing a message under context `ctx` with `secret`.
+	///
+	/// Returns [Error::ContextTooLong] if 'ctx' is longer than 255 bytes
+	pub fn new(secret: &'a SecretKey<P>, ctx: Option<&[u8]>) -> Result<Self, Error> {
+		Signer::from_bytes(secret.bytes.as_ref(), ctx)
+	}
+
+	/// As [Signer::new] for an encoded secret key of the right length.
+	pub(crate) fn from_bytes(sk: &'a [u8], ctx: Option<&[u8]>) -> Result<Self, Error> {
+		let state = start_mu(secret_tr(sk), 0, check_ctx(ctx)?, &[]);
+		Ok(Signer { sk, state, _marker: PhantomData })
+	}
+
+	/// Absorb the next chunk of the message.
+	pub fn update(&mut self, chunk: &[u8]) -> &mut Self {
+		fips202::shake256_absorb(&mut self.state, chunk, chunk.len());
+		self
+	}
+
+	/// Absorb everything `reader` yields, returning the number of bytes read.
+	#[cfg(feature = "std")]
+	pub fn update_reader(&mut self, reader: impl io::Read) -> io::Result<u64> {
+		update_reader(&mut self.state, reader)
+	}
+
+	/// Sign the message absorbed so far.
+	///
+	/// # Arguments
+	///
+	/// * 'hedged' - wether to use RNG or not
+	///
+	/// Returns Signature
+	pub fn sign(mut self, hedged: bool) -> Result<Signature<P>, Error> {
+		let mut mu = finish_mu(&mut self.state);
+		let sig = sign_mu_with::<P>(self.sk, &mu, hedged);
+		mu.zeroize();
+		sig
+	}
+
+	/// Sign the message absorbed so far, hedged with randomness drawn from `rng`.
+	///
+	/// Returns Signature, or [Error::MissingRng] if 'rng' fails
+	pub fn sign_with_rng(
+		mut self,
+		rng: &mut (impl RngCore + CryptoRng),
+	) -> Result<Signature<P>, Error> {
+		let mut rnd = [0u8; params::SEEDBYTES];
+		crate::rng_bytes(rng, &mut rnd)?;
+		let mut mu = finish_mu(&mut self.state);
+		let mut sig = P::SignatureBytes::zeroed();
+		let result = crate::sign::sign_mu_with_rnd::<P>(sig.as_mut(), &mu, self.sk, &rnd);
+		mu.zeroize();
+		rnd.zeroize();
+		result.map(|()| sig)
+	}
+}
+
+impl<P: ParameterSet> Drop for Signer<'_, P> {
+	fn drop(&mut self) {
+		self.state.zeroize();
+	}
+}
+
+#[cfg(feature = "std")]
+impl<P: ParameterSet> io::Write for Signer<'_, P> {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		self.update(buf);
+		Ok(buf.len())
+	}
+
+	fn flush(&mut self) -> io::Result<()> {
+		Ok(())
+	}
+}
+
+/// Verifies a signature over a message fed in chunks; see [PublicKey::verifier].
+pub struct Verifier<'a, P: ParameterSet> {
+	public: &'a PublicKey<P>,
+	/// H(pk), kept so that [Verifier::verify] does not hash pk again.
+	tr: [u8; params::TR_BYTES],
+	state: KeccakState,
+}
+
+impl<'a, P: ParameterSet> Verifier<'a, P> {
+	/// Start verifying a message under context `ctx` against `public`.
+	///
+	/// Returns [Error::ContextTooLong] if 'ctx' is longer than 255 bytes
+	pub fn new(public: &'a PublicKey<P>, ctx: Option<&[u8]>) -> Result<Self, Error> {
+		let ctx = check_ctx(ctx)?;
+		let tr = public_tr(public.bytes.as_ref());
+		Ok(Verifier { public, tr, state: start_mu(&tr, 0, ctx, &[]) })
+	}
+
+	/// Absorb the next chunk of the message.
+	pub fn update(&mut self, chunk: &[u8]) -> &mut Self {
+		fips202::shake256_absorb(&mut self.state, chunk, chunk.len());
+		self
+	}
+
+	/// Absorb everything `reader` yields, returning the number of bytes read.
+	#[cfg(feature = "std")]
+	pub fn update_reader(&mut self, reader: impl io::Read) -> io::Result<u64> {
+		update_reader(&mut self.state, reader)
+	}
+
+	/// Verify `sig` over the message absorbed so far.
+	///
+	/// Returns Ok(()) if the verification process was successful, otherwise the reason it failed
+	pub fn verify(mut self, sig: &[u8]) -> Result<(), Error> {
+		let mu = finish_mu(&mut self.state);
+		self.public.verify_mu_with_tr(&self.tr, &mu, sig)
+	}
+}
+
+#[cfg(feature = "std")]
+impl<P: ParameterSet> io::Write for Verifier<'_, P> {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		self.update(buf);
+		Ok(buf.len())
+	}
+
+	fn flush(&mut self) -> io::Result<()> {
+		Ok(())
+	}
+}
+
+/// tr = H(pk) of an encoded public key.
+pub(crate) fn public_tr(pk: &[u8]) -> [u8; params::TR_BYTES] {
+	let mut tr = [0u8; params::TR_BYTES];
+	fips202::shake256(&mut tr, params::TR_BYTES, pk, pk.len());
+	tr
+}
+
+/// The tr stored in an encoded secret key.
+pub(crate) fn secret_tr(sk: &[u8]) -> &[u8] {
+	&sk[2 * params::SEEDBYTES..2 * params::SEEDBYTES + params::TR_BYTES]
+}
+
+/// Absorb tr and the prefix of M' that precedes the message: domain || |ctx| || ctx || oid.
+/// Assumes ctx is at most 255 bytes.
+pub(crate) fn start_mu(tr: &[u8], domain: u8, ctx: &[u8], oid: &[u8]) -> KeccakState {
+	let mut state = KeccakState::default();
+	fips202::shake256_absorb(&mut state, tr, params::TR_BYTES);
+	fips202::shake256_absorb(&mut state, &[domain, ctx.len() as u8], 2);
+	fips202::shake256_absorb(&mut state, ctx, ctx.len());
+	fips202::shake256_absorb(&mut state, oid, oid.len());
+	state
+}
+
+pub(crate) fn finish_mu(state: &mut KeccakState) -> [u8; params::CRHBYTES] {
+	let mut mu = [0u8; params::CRHBYTES];
+	fips202::shake256_finalize(state);
+	fips202::shake256_squeeze(&mut mu, params::CRHBYTES, state);
+	mu
+}
+
+#[cfg(feature = "std")]
+fn update_reader(state: &mut KeccakState, mut reader: impl io::Read) -> io::Result<u64> {
+	let mut buf = [0u8; 8192];
+	let mut total = 0u64;
+	loop {
+		let n = match reader.read(&mut buf) {
+			Ok(0) => break,
+			Ok(n) => n,
+			Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+			Err(e) => return Err(e),
+		};
+		fips202::shake256_absorb(state, &buf[..n], n);
+		total += n as u64;
+	}
+	buf.zeroize();
+	Ok(total)
+}
+
+#[cfg(test)]
+#[cfg(feature = "std")]
+mod tests {
+	use super::*;
+	use crate::ml_dsa::{Keypair, MlDsa44, MlDsa65, MlDsa87};
+
+	fn matches_one_shot<P: ParameterSet>() {
+		let keys = Keypair::<P>::generate(Some(&[4u8; params::SEEDBYTES])).unwrap();
+		let msg: Vec<u8> = (0..5000u32).map(|i| (i * 7) as u8).collect();
+		let expected = keys.sign(&msg, Some(b"ctx"), false).unwrap();
+
+		// Chunk sizes either side of the SHAKE256 rate.
+		for chunk in [1, 135, 136, 137, 4096] {
+			let mut signer = keys.secret.signer(Some(b"ctx")).unwrap();
+			for piece in msg.chunks(chunk) {
+				signer.update(piece);
+			}
+			assert_eq!(signer.sign(false).unwrap(), expected, "chunk size {chunk}");
+
+			let mut verifier = keys.public.verifier(Some(b"ctx")).unwrap();
+			for piece in msg.chunks(chunk) {
+				verifier.update(piece);
+			}
+			assert!(verifier.verify(expected.as_ref()).is_ok());
+		}
+
+		let mut signer = keys.secret.signer(Some(b"ctx")).unwrap();
+		assert_eq!(signer.update_reader(&msg[..]).unwrap(), msg.len() as u64);
+		let sig = signer.sign(true).unwrap();
+		assert!(keys.verify(&msg, sig.as_ref(), Some(b"ctx")).is_ok());
+
+		let mut verifier = keys.public.verifier(Some(b"ctx")).unwrap();
+		io::copy(&mut &msg[..], &mut verifier).unwrap();
+		assert!(verifier.verify(sig.as_ref()).is_ok());
+
+		let mut verifier = keys.public.verifier(None).unwrap();
+		verifier.update(&msg);
+		assert_eq!(verifier.verify(sig.as_ref()), Err(Error::ChallengeMismatch));
+		assert_eq!(keys.secret.signer(Some(&[0; 256])).map(|_| ()), Err(Error::ContextTooLong));
+		assert_eq!(keys.public.verifier(Some(&[0; 256])).map(|_| ()), Err(Error::ContextTooLong));
+	}
+
+	#[test]
+	fn matches_one_shot_all_levels() {
+		matches_one_shot::<MlDsa44>();
+		matches_one_shot::<MlDsa65>();
+		matches_one_shot::<MlDsa87>();
+	}
+}
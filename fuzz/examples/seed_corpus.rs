@@ -0,0 +1,125 @@
+//! Write starting inputs for every fuzz target into `corpus/<target>/`, built from the NIST KAT
+//! file in `test_vectors` without any network access:
+//!
+//! * the KAT key pairs and signatures, which are ML-DSA-87 ones;
+//! * the KAT messages signed at every level with keys generated from the KAT seeds, so that each
+//!   level starts from inputs that verify;
+//! * a handful of wallet derivation paths.
+//!
+//! Usage: `cargo run --example seed_corpus [entries]`, taking the first `entries` KAT entries (10
+//! by default).
+
+use std::{fs, path::Path};
+
+use qp_rusty_crystals_dilithium::{
+	params::{self, SEEDBYTES},
+	sign, Keypair, MlDsa44, MlDsa65, MlDsa87, ParameterSet,
+};
+use qp_rusty_crystals_fuzz::{
+	parse_kat, KeypairInput, Level, PathInput, PublicKeyInput, SignInput, SignatureInput,
+	VerifyInput,
+};
+
+macro_rules! legacy {
+	($lvl:ident, $seed:expr, $msg:expr) => {{
+		let mut pk = vec![0u8; params::$lvl::PUBLICKEYBYTES];
+		let mut sk = vec![0u8; params::$lvl::SECRETKEYBYTES];
+		sign::$lvl::keypair(&mut pk, &mut sk, Some($seed)).unwrap();
+		let mut sig = vec![0u8; params::$lvl::SIGNBYTES];
+		sign::$lvl::signature(&mut sig, $msg, &sk, false).unwrap();
+		Signed { pk, sk, sig }
+	}};
+}
+
+const PATHS: [&str; 6] = [
+	"m",
+	"m/44'/189189'/0'/0'/0'",
+	"m/44'/189189189'/0'/0'/0'",
+	"m/44'/189189'/0'/0/0",
+	"m/2147483647'/0'",
+	"m/44'/x'",
+];
+
+fn main() {
+	let entries = std::env::args().nth(1).map_or(10, |n| n.parse().expect("entries: a number"));
+	let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+	let kat = fs::read_to_string(root.join("../test_vectors/PQCsignKAT_Dilithium5.rsp"))
+		.expect("test_vectors/PQCsignKAT_Dilithium5.rsp");
+	let corpus = Corpus(root.join("corpus"));
+
+	for (i, kat) in parse_kat(&kat).iter().take(entries).enumerate() {
+		let seed: [u8; SEEDBYTES] = kat.seed[..SEEDBYTES].try_into().unwrap();
+		let signed = Signed { pk: kat.pk.clone(), sk: kat.sk.clone(), sig: kat.sig.clone() };
+		corpus.add(&format!("kat-{i}"), Level::MlDsa87, &signed, &kat.msg);
+		for level in Level::ALL {
+			let name = format!("signed-{i}-{level:?}");
+			corpus.add(&name, level, &Signed::new(level, &seed, &kat.msg), &kat.msg);
+			let input = SignInput {
+				level,
+				seed,
+				rnd: [i as u8; SEEDBYTES],
+				ctx: (i % 2 == 1).then(|| kat.msg[..i.min(kat.msg.len())].to_vec()),
+				msg: kat.msg.clone(),
+			};
+			corpus.write("sign_verify", &name, &input.encode());
+		}
+	}
+	for (i, path) in PATHS.iter().enumerate() {
+		corpus.write(
+			"hd_path",
+			&format!("path-{i}"),
+			&PathInput { path: path.to_string() }.encode(),
+		);
+	}
+}
+
+/// A key pair and a signature of one message.
+struct Signed {
+	pk: Vec<u8>,
+	sk: Vec<u8>,
+	sig: Vec<u8>,
+}
+
+impl Signed {
+	/// Deterministically sign `msg` with the `level` key pair generated from `seed`.
+	fn new(level: Level, seed: &[u8; SEEDBYTES], msg: &[u8]) -> Self {
+		match level {
+			Level::MlDsa44 => Signed::ml_dsa::<MlDsa44>(seed, msg),
+			Level::MlDsa65 => Signed::ml_dsa::<MlDsa65>(seed, msg),
+			Level::MlDsa87 => Signed::ml_dsa::<MlDsa87>(seed, msg),
+			Level::Dilithium2 => legacy!(lvl2, seed, msg),
+			Level::Dilithium3 => legacy!(lvl3, seed, msg),
+			Level::Dilithium5 => legacy!(lvl5, seed, msg),
+		}
+	}
+
+	fn ml_dsa<P: ParameterSet>(seed: &[u8; SEEDBYTES], msg: &[u8]) -> Self {
+		let keys = Keypair::<P>::generate(Some(seed)).unwrap();
+		Signed {
+			pk: keys.public.to_bytes().as_ref().to_vec(),
+			sk: keys.secret.to_bytes().as_ref().to_vec(),
+			sig: keys.sign(msg, None, false).unwrap().as_ref().to_vec(),
+		}
+	}
+}
+
+struct Corpus(std::path::PathBuf);
+
+impl Corpus {
+	/// Seed the targets that take keys or signatures with `signed` and its message.
+	fn add(&self, name: &str, level: Level, signed: &Signed, msg: &[u8]) {
+		let Signed { pk, sk, sig } = signed;
+		self.write("unpack_sig", name, &SignatureInput { level, sig: sig.clone() }.encode());
+		self.write("unpack_pk", name, &PublicKeyInput { level, pk: pk.clone() }.encode());
+		let bytes = [&sk[..], pk].concat();
+		self.write("keypair_from_bytes", name, &KeypairInput { level, bytes }.encode());
+		let (pk, sig, msg) = (pk.clone(), sig.clone(), msg.to_vec());
+		self.write("verify", name, &VerifyInput { level, ctx: None, pk, sig, msg }.encode());
+	}
+
+	fn write(&self, target: &str, name: &str, bytes: &[u8]) {
+		let dir = self.0.join(target);
+		fs::create_dir_all(&dir).unwrap();
+		fs::write(dir.join(name), bytes).unwrap();
+	}
+}
@@ -0,0 +1,24 @@
+#![no_main]
+
+//! Derivation path parsing. A path that derives must pass `check_path`, a path `check_path`
+//! rejects must fail to derive with the same error, and derivation is deterministic.
+
+use std::sync::OnceLock;
+
+use libfuzzer_sys::fuzz_target;
+use qp_rusty_crystals_fuzz::PathInput;
+use qp_rusty_crystals_hdwallet::HDLattice;
+
+fuzz_target!(|input: PathInput| {
+	static LATTICE: OnceLock<HDLattice> = OnceLock::new();
+	let lattice = LATTICE.get_or_init(|| HDLattice::from_seed([7u8; 64]).unwrap());
+
+	let checked = lattice.check_path(&input.path);
+	let derived = lattice.derive_entropy(&input.path);
+	match (&checked, &derived) {
+		(Err(err), _) => assert_eq!(derived.as_ref().err(), Some(err)),
+		(Ok(()), Ok(entropy)) =>
+			assert_eq!(lattice.derive_entropy(&input.path).as_ref(), Ok(entropy)),
+		(Ok(()), Err(_)) => {},
+	}
+});
@@ -0,0 +1,33 @@
+#![no_main]
+
+//! Key pair parsing. `from_bytes` only checks the length, `from_bytes_checked` also that the
+//! halves belong together, and a key pair that passes the check signs and verifies.
+
+use libfuzzer_sys::fuzz_target;
+use qp_rusty_crystals_dilithium::{Keypair, MlDsa44, MlDsa65, MlDsa87, ParameterSet};
+use qp_rusty_crystals_fuzz::{KeypairInput, Level};
+
+fuzz_target!(|input: KeypairInput| {
+	// The pre-standard levels have no Keypair type; they stand in for the ML-DSA level of the
+	// same strength, with their own key sizes giving length errors.
+	match input.level {
+		Level::MlDsa44 | Level::Dilithium2 => ml_dsa::<MlDsa44>(&input.bytes),
+		Level::MlDsa65 | Level::Dilithium3 => ml_dsa::<MlDsa65>(&input.bytes),
+		Level::MlDsa87 | Level::Dilithium5 => ml_dsa::<MlDsa87>(&input.bytes),
+	}
+});
+
+fn ml_dsa<P: ParameterSet>(bytes: &[u8]) {
+	let checked = Keypair::<P>::from_bytes_checked(bytes);
+	let Ok(keys) = Keypair::<P>::from_bytes(bytes) else {
+		assert!(checked.is_err());
+		return;
+	};
+	assert_eq!(keys.to_bytes().as_ref(), bytes);
+	let derived = keys.secret.public_key();
+	assert_eq!(checked.is_ok(), derived.is_ok_and(|public| public == keys.public));
+	if checked.is_ok() {
+		let sig = keys.sign(b"fuzz", None, false).unwrap();
+		assert_eq!(keys.verify(b"fuzz", sig.as_ref(), None), Ok(()));
+	}
+}
@@ -0,0 +1,64 @@
+#![no_main]
+
+//! Differential sign and verify round trips. The high-level signer, `sign_internal` over M' and
+//! the low-memory signer must produce the same signature, which must verify for its message and
+//! context and for nothing else.
+
+use libfuzzer_sys::fuzz_target;
+use qp_rusty_crystals_dilithium::{
+	params, sign, Error, Keypair, MlDsa44, MlDsa65, MlDsa87, ParameterSet,
+};
+use qp_rusty_crystals_fuzz::{Level, SignInput};
+
+macro_rules! legacy {
+	($lvl:ident, $input:expr) => {{
+		let mut pk = [0u8; params::$lvl::PUBLICKEYBYTES];
+		let mut sk = [0u8; params::$lvl::SECRETKEYBYTES];
+		sign::$lvl::keypair(&mut pk, &mut sk, Some(&$input.seed)).unwrap();
+		let mut sig = [0u8; params::$lvl::SIGNBYTES];
+		sign::$lvl::signature(&mut sig, &$input.msg, &sk, false).unwrap();
+		assert!(sign::$lvl::verify(&sig, &$input.msg, &pk));
+		assert!(!sign::$lvl::verify(&sig, &[&$input.msg[..], &[0]].concat(), &pk));
+	}};
+}
+
+fuzz_target!(|input: SignInput| {
+	match input.level {
+		Level::MlDsa44 => ml_dsa::<MlDsa44>(&input),
+		Level::MlDsa65 => ml_dsa::<MlDsa65>(&input),
+		Level::MlDsa87 => ml_dsa::<MlDsa87>(&input),
+		Level::Dilithium2 => legacy!(lvl2, input),
+		Level::Dilithium3 => legacy!(lvl3, input),
+		Level::Dilithium5 => legacy!(lvl5, input),
+	}
+});
+
+fn ml_dsa<P: ParameterSet>(input: &SignInput) {
+	let keys = Keypair::<P>::generate(Some(&input.seed)).unwrap();
+	let (msg, ctx) = (&input.msg[..], input.ctx.as_deref());
+	let signed = keys.sign(msg, ctx, false);
+	let ctx = ctx.unwrap_or_default();
+	if ctx.len() > 255 {
+		assert_eq!(signed, Err(Error::ContextTooLong));
+		return;
+	}
+	let sig = signed.unwrap();
+	assert_eq!(keys.verify(msg, sig.as_ref(), Some(ctx)), Ok(()));
+
+	let sk = keys.secret.to_bytes();
+	let m_prime = [&[0, ctx.len() as u8][..], ctx, msg].concat();
+	let mut internal = vec![0u8; P::SIGNBYTES];
+	sign::sign_internal::<P>(&mut internal, &m_prime, sk.as_ref(), &[0u8; params::SEEDBYTES])
+		.unwrap();
+	assert_eq!(internal, sig.as_ref());
+
+	sign::sign_internal::<P>(&mut internal, &m_prime, sk.as_ref(), &input.rnd).unwrap();
+	let mut low_memory = vec![0u8; P::SIGNBYTES];
+	sign::signature_low_memory_with_rnd::<P>(&mut low_memory, &m_prime, sk.as_ref(), &input.rnd)
+		.unwrap();
+	assert_eq!(low_memory, internal);
+	assert_eq!(keys.verify(msg, &internal, Some(ctx)), Ok(()));
+
+	let other = [msg, &[0]].concat();
+	assert_eq!(keys.verify(&other, sig.as_ref(), Some(ctx)), Err(Error::ChallengeMismatch));
+}
@@ -0,0 +1,49 @@
+#![no_main]
+
+//! Public key decoding. Every bit pattern of the right length is a public key, and decoding then
+//! encoding gives the same bytes back.
+
+use libfuzzer_sys::fuzz_target;
+use qp_rusty_crystals_dilithium::{
+	packing, params,
+	polyvec::{self, Polyvec},
+	MlDsa44, MlDsa65, MlDsa87, ParameterSet, PublicKey,
+};
+use qp_rusty_crystals_fuzz::{Level, PublicKeyInput};
+
+macro_rules! legacy {
+	($lvl:ident, $pk:expr) => {{
+		let mut rho = [0u8; params::SEEDBYTES];
+		let mut t1 = polyvec::$lvl::Polyveck::default();
+		packing::$lvl::unpack_pk(&mut rho, &mut t1, $pk);
+		let mut again = vec![0u8; $pk.len()];
+		packing::$lvl::pack_pk(&mut again, &rho, &t1);
+		assert_eq!(again, $pk);
+	}};
+}
+
+fuzz_target!(|input: PublicKeyInput| {
+	let pk = &input.pk[..];
+	match input.level {
+		Level::MlDsa44 => ml_dsa::<MlDsa44, { params::ml_dsa_44::K }>(pk),
+		Level::MlDsa65 => ml_dsa::<MlDsa65, { params::ml_dsa_65::K }>(pk),
+		Level::MlDsa87 => ml_dsa::<MlDsa87, { params::ml_dsa_87::K }>(pk),
+		Level::Dilithium2 => legacy!(lvl2, pk),
+		Level::Dilithium3 => legacy!(lvl3, pk),
+		Level::Dilithium5 => legacy!(lvl5, pk),
+	}
+});
+
+fn ml_dsa<P: ParameterSet, const K: usize>(pk: &[u8]) {
+	let mut rho = [0u8; params::SEEDBYTES];
+	let mut t1 = Polyvec::<K>::default();
+	packing::unpack_pk::<K>(&mut rho, &mut t1, pk);
+	assert!(t1.vec.iter().all(|poly| poly.coeffs.iter().all(|&c| (0..1 << 10).contains(&c))));
+	let mut again = vec![0u8; pk.len()];
+	packing::pack_pk::<K>(&mut again, &rho, &t1);
+	assert_eq!(again, pk);
+
+	let public = PublicKey::<P>::from_bytes(pk).unwrap();
+	assert_eq!(public.to_bytes().as_ref(), pk);
+	public.prepare();
+}
@@ -0,0 +1,56 @@
+#![no_main]
+
+//! Signature decoding. Decoding is strict, so a signature that decodes must encode back to the
+//! same bytes, and `check_signature` must reach the same verdict as the decoder and norm bound.
+
+use libfuzzer_sys::fuzz_target;
+use qp_rusty_crystals_dilithium::{
+	packing, params,
+	polyvec::{self, Polyvec},
+	sign, MlDsa44, MlDsa65, MlDsa87, ParameterSet,
+};
+use qp_rusty_crystals_fuzz::{Level, SignatureInput};
+
+macro_rules! legacy {
+	($lvl:ident, $sig:expr) => {{
+		let mut c = [0u8; params::SEEDBYTES];
+		let mut z = polyvec::$lvl::Polyvecl::default();
+		let mut h = polyvec::$lvl::Polyveck::default();
+		if packing::$lvl::unpack_sig(&mut c, &mut z, &mut h, $sig) {
+			let mut again = vec![0u8; $sig.len()];
+			packing::$lvl::pack_sig(&mut again, Some(&c), &z, &h);
+			assert_eq!(again, $sig);
+		}
+	}};
+}
+
+fuzz_target!(|input: SignatureInput| {
+	let sig = &input.sig[..];
+	match input.level {
+		Level::MlDsa44 =>
+			ml_dsa::<MlDsa44, { params::ml_dsa_44::K }, { params::ml_dsa_44::L }>(sig),
+		Level::MlDsa65 =>
+			ml_dsa::<MlDsa65, { params::ml_dsa_65::K }, { params::ml_dsa_65::L }>(sig),
+		Level::MlDsa87 =>
+			ml_dsa::<MlDsa87, { params::ml_dsa_87::K }, { params::ml_dsa_87::L }>(sig),
+		Level::Dilithium2 => legacy!(lvl2, sig),
+		Level::Dilithium3 => legacy!(lvl3, sig),
+		Level::Dilithium5 => legacy!(lvl5, sig),
+	}
+});
+
+fn ml_dsa<P: ParameterSet, const K: usize, const L: usize>(sig: &[u8]) {
+	let mut c = [0u8; params::C_DASH_BYTES_MAX];
+	let (mut z, mut h) = (Polyvec::<L>::default(), Polyvec::<K>::default());
+	let checked = sign::check_signature::<P>(sig);
+	match packing::unpack_sig::<P, K, L>(&mut c, &mut z, &mut h, sig) {
+		Ok(()) => {
+			let mut again = vec![0u8; sig.len()];
+			packing::pack_sig::<P, K, L>(&mut again, Some(&c), &z, &h);
+			assert_eq!(again, sig);
+			let in_bound = polyvec::chknorm(&z, (P::GAMMA1 - P::BETA) as i32) == 0;
+			assert_eq!(checked.is_ok(), in_bound);
+		},
+		Err(err) => assert_eq!(checked, Err(err)),
+	}
+}
@@ -0,0 +1,36 @@
+#![no_main]
+
+//! Verification of arbitrary keys, signatures, messages and contexts. It must never panic, and
+//! every way of verifying must reach the same verdict.
+
+use libfuzzer_sys::fuzz_target;
+use qp_rusty_crystals_dilithium::{sign, MlDsa44, MlDsa65, MlDsa87, ParameterSet, PublicKey};
+use qp_rusty_crystals_fuzz::{Level, VerifyInput};
+
+fuzz_target!(|input: VerifyInput| {
+	let VerifyInput { level, ctx, pk, sig, msg } = input;
+	let ctx = ctx.as_deref();
+	match level {
+		Level::MlDsa44 => ml_dsa::<MlDsa44>(&pk, &sig, &msg, ctx),
+		Level::MlDsa65 => ml_dsa::<MlDsa65>(&pk, &sig, &msg, ctx),
+		Level::MlDsa87 => ml_dsa::<MlDsa87>(&pk, &sig, &msg, ctx),
+		Level::Dilithium2 => _ = sign::lvl2::verify(&sig, &msg, &pk),
+		Level::Dilithium3 => _ = sign::lvl3::verify(&sig, &msg, &pk),
+		Level::Dilithium5 => _ = sign::lvl5::verify(&sig, &msg, &pk),
+	}
+});
+
+fn ml_dsa<P: ParameterSet>(pk: &[u8], sig: &[u8], msg: &[u8], ctx: Option<&[u8]>) {
+	let public = PublicKey::<P>::from_bytes(pk).unwrap();
+	let result = public.verify(msg, sig, ctx);
+	assert_eq!(public.prepare().verify(msg, sig, ctx), result);
+
+	let ctx = ctx.unwrap_or_default();
+	if ctx.len() <= 255 {
+		let m_prime = [&[0, ctx.len() as u8][..], ctx, msg].concat();
+		assert_eq!(sign::verify::<P>(sig, &m_prime, pk), result);
+	}
+	if result.is_ok() {
+		assert_eq!(sign::check_signature::<P>(sig), Ok(()));
+	}
+}
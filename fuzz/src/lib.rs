@@ -0,0 +1,294 @@
+//! Inputs shared by the fuzz targets in `fuzz_targets/`.
+//!
+//! Each input implements [Arbitrary] by reading typed fields off the fuzzer's bytes: a parameter
+//! set first, then buffers cut or zero-padded to the sizes that parameter set expects, so that
+//! mutations reach the decoders instead of stopping at a length check. `encode` writes an input
+//! back in the same layout, which is how `seed_corpus` turns the KAT file into starting inputs.
+
+use arbitrary::{Arbitrary, Result, Unstructured};
+use qp_rusty_crystals_dilithium::params;
+
+/// A parameter set: the three ML-DSA levels and the pre-standard Dilithium ones in `sign::lvl*`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Level {
+	MlDsa44,
+	MlDsa65,
+	MlDsa87,
+	Dilithium2,
+	Dilithium3,
+	Dilithium5,
+}
+
+impl Level {
+	pub const ALL: [Level; 6] = [
+		Level::MlDsa44,
+		Level::MlDsa65,
+		Level::MlDsa87,
+		Level::Dilithium2,
+		Level::Dilithium3,
+		Level::Dilithium5,
+	];
+
+	pub fn public_key_bytes(self) -> usize {
+		match self {
+			Level::MlDsa44 => params::ml_dsa_44::PUBLICKEYBYTES,
+			Level::MlDsa65 => params::ml_dsa_65::PUBLICKEYBYTES,
+			Level::MlDsa87 => params::ml_dsa_87::PUBLICKEYBYTES,
+			Level::Dilithium2 => params::lvl2::PUBLICKEYBYTES,
+			Level::Dilithium3 => params::lvl3::PUBLICKEYBYTES,
+			Level::Dilithium5 => params::lvl5::PUBLICKEYBYTES,
+		}
+	}
+
+	pub fn secret_key_bytes(self) -> usize {
+		match self {
+			Level::MlDsa44 => params::ml_dsa_44::SECRETKEYBYTES,
+			Level::MlDsa65 => params::ml_dsa_65::SECRETKEYBYTES,
+			Level::MlDsa87 => params::ml_dsa_87::SECRETKEYBYTES,
+			Level::Dilithium2 => params::lvl2::SECRETKEYBYTES,
+			Level::Dilithium3 => params::lvl3::SECRETKEYBYTES,
+			Level::Dilithium5 => params::lvl5::SECRETKEYBYTES,
+		}
+	}
+
+	pub fn signature_bytes(self) -> usize {
+		match self {
+			Level::MlDsa44 => params::ml_dsa_44::SIGNBYTES,
+			Level::MlDsa65 => params::ml_dsa_65::SIGNBYTES,
+			Level::MlDsa87 => params::ml_dsa_87::SIGNBYTES,
+			Level::Dilithium2 => params::lvl2::SIGNBYTES,
+			Level::Dilithium3 => params::lvl3::SIGNBYTES,
+			Level::Dilithium5 => params::lvl5::SIGNBYTES,
+		}
+	}
+
+	fn to_byte(self) -> u8 {
+		Level::ALL.iter().position(|&level| level == self).unwrap() as u8
+	}
+}
+
+impl<'a> Arbitrary<'a> for Level {
+	fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+		let [byte] = fixed::<1>(u)?;
+		Ok(Level::ALL[usize::from(byte) % Level::ALL.len()])
+	}
+}
+
+/// A signature of the size `level` expects. Layout: level byte, signature.
+#[derive(Debug)]
+pub struct SignatureInput {
+	pub level: Level,
+	pub sig: Vec<u8>,
+}
+
+impl<'a> Arbitrary<'a> for SignatureInput {
+	fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+		let level = Level::arbitrary(u)?;
+		Ok(SignatureInput { level, sig: sized(u, level.signature_bytes())? })
+	}
+}
+
+impl SignatureInput {
+	pub fn encode(&self) -> Vec<u8> {
+		[&[self.level.to_byte()][..], &self.sig].concat()
+	}
+}
+
+/// A public key of the size `level` expects. Layout: level byte, public key.
+#[derive(Debug)]
+pub struct PublicKeyInput {
+	pub level: Level,
+	pub pk: Vec<u8>,
+}
+
+impl<'a> Arbitrary<'a> for PublicKeyInput {
+	fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+		let level = Level::arbitrary(u)?;
+		Ok(PublicKeyInput { level, pk: sized(u, level.public_key_bytes())? })
+	}
+}
+
+impl PublicKeyInput {
+	pub fn encode(&self) -> Vec<u8> {
+		[&[self.level.to_byte()][..], &self.pk].concat()
+	}
+}
+
+/// Encoded secret and public keys. Layout: level byte, a byte whose low bit set keeps the rest
+/// as it is, clear cuts or pads it to the key pair size, then the key pair.
+#[derive(Debug)]
+pub struct KeypairInput {
+	pub level: Level,
+	pub bytes: Vec<u8>,
+}
+
+impl<'a> Arbitrary<'a> for KeypairInput {
+	fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+		let level = Level::arbitrary(u)?;
+		let [mode] = fixed::<1>(u)?;
+		let bytes = match mode & 1 {
+			0 => sized(u, level.secret_key_bytes() + level.public_key_bytes())?,
+			_ => u.bytes(u.len())?.to_vec(),
+		};
+		Ok(KeypairInput { level, bytes })
+	}
+}
+
+impl KeypairInput {
+	pub fn encode(&self) -> Vec<u8> {
+		[&[self.level.to_byte(), 0][..], &self.bytes].concat()
+	}
+}
+
+/// A verification request. Layout: level byte, context, public key, signature, message.
+#[derive(Debug)]
+pub struct VerifyInput {
+	pub level: Level,
+	pub ctx: Option<Vec<u8>>,
+	pub pk: Vec<u8>,
+	pub sig: Vec<u8>,
+	pub msg: Vec<u8>,
+}
+
+impl<'a> Arbitrary<'a> for VerifyInput {
+	fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+		let level = Level::arbitrary(u)?;
+		let ctx = context(u)?;
+		let pk = sized(u, level.public_key_bytes())?;
+		let sig = sized(u, level.signature_bytes())?;
+		Ok(VerifyInput { level, ctx, pk, sig, msg: u.bytes(u.len())?.to_vec() })
+	}
+}
+
+impl VerifyInput {
+	pub fn encode(&self) -> Vec<u8> {
+		let head = [&[self.level.to_byte()][..], &encode_context(self.ctx.as_deref())].concat();
+		[head, self.pk.clone(), self.sig.clone(), self.msg.clone()].concat()
+	}
+}
+
+/// A key generation seed, signing randomness and what to sign. Layout: level byte, seed, rnd,
+/// context, message.
+#[derive(Debug)]
+pub struct SignInput {
+	pub level: Level,
+	pub seed: [u8; params::SEEDBYTES],
+	pub rnd: [u8; params::SEEDBYTES],
+	pub ctx: Option<Vec<u8>>,
+	pub msg: Vec<u8>,
+}
+
+impl<'a> Arbitrary<'a> for SignInput {
+	fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+		Ok(SignInput {
+			level: Level::arbitrary(u)?,
+			seed: fixed(u)?,
+			rnd: fixed(u)?,
+			ctx: context(u)?,
+			msg: u.bytes(u.len())?.to_vec(),
+		})
+	}
+}
+
+impl SignInput {
+	pub fn encode(&self) -> Vec<u8> {
+		let head = [&[self.level.to_byte()][..], &self.seed, &self.rnd].concat();
+		[head, encode_context(self.ctx.as_deref()), self.msg.clone()].concat()
+	}
+}
+
+/// A derivation path. Layout: a byte whose low bit clear takes the rest as the path text; set,
+/// the byte's upper bits give up to 15 indices of four little-endian bytes each, hardened when
+/// the top bit is set as in BIP32, formatted as "m/i/j'/...".
+#[derive(Debug)]
+pub struct PathInput {
+	pub path: String,
+}
+
+impl<'a> Arbitrary<'a> for PathInput {
+	fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+		let [mode] = fixed::<1>(u)?;
+		if mode & 1 == 0 {
+			return Ok(PathInput { path: String::from_utf8_lossy(u.bytes(u.len())?).into_owned() });
+		}
+		let mut path = String::from("m");
+		for _ in 0..(mode >> 1) % 16 {
+			let index = u32::from_le_bytes(fixed(u)?);
+			let hardened = if index >> 31 == 1 { "'" } else { "" };
+			path += &format!("/{}{hardened}", index & 0x7fff_ffff);
+		}
+		Ok(PathInput { path })
+	}
+}
+
+impl PathInput {
+	pub fn encode(&self) -> Vec<u8> {
+		[&[0][..], self.path.as_bytes()].concat()
+	}
+}
+
+/// One entry of a `PQCsignKAT_*.rsp` file.
+#[derive(Debug)]
+pub struct Kat {
+	pub seed: Vec<u8>,
+	pub msg: Vec<u8>,
+	pub pk: Vec<u8>,
+	pub sk: Vec<u8>,
+	/// The signature, split off the signed message `sm = sig || msg`.
+	pub sig: Vec<u8>,
+}
+
+/// Parse the entries of a `PQCsignKAT_*.rsp` file.
+pub fn parse_kat(text: &str) -> Vec<Kat> {
+	let mut entries = Vec::new();
+	let mut fields = std::collections::HashMap::new();
+	for line in text.lines().chain(["count = end"]) {
+		let Some((name, value)) = line.split_once(" = ") else { continue };
+		if name == "count" && fields.contains_key("sm") {
+			let mut take = |name: &str| hex(fields.remove(name).unwrap());
+			let (seed, msg, pk, sk, sm) =
+				(take("seed"), take("msg"), take("pk"), take("sk"), take("sm"));
+			let sig = sm[..sm.len() - msg.len()].to_vec();
+			entries.push(Kat { seed, msg, pk, sk, sig });
+		}
+		fields.insert(name, value);
+	}
+	entries
+}
+
+fn hex(text: &str) -> Vec<u8> {
+	(0..text.len())
+		.step_by(2)
+		.map(|i| u8::from_str_radix(&text[i..i + 2], 16).unwrap())
+		.collect()
+}
+
+/// Exactly `N` bytes, zero-padded once the input runs out.
+fn fixed<const N: usize>(u: &mut Unstructured<'_>) -> Result<[u8; N]> {
+	let mut out = [0u8; N];
+	u.fill_buffer(&mut out)?;
+	Ok(out)
+}
+
+/// Exactly `len` bytes, zero-padded once the input runs out.
+fn sized(u: &mut Unstructured<'_>, len: usize) -> Result<Vec<u8>> {
+	let mut out = vec![0u8; len];
+	u.fill_buffer(&mut out)?;
+	Ok(out)
+}
+
+/// A context of two little-endian length bytes and the context itself. 0xffff stands for no
+/// context, and lengths past 255 are kept so that ContextTooLong is reached.
+fn context(u: &mut Unstructured<'_>) -> Result<Option<Vec<u8>>> {
+	match u16::from_le_bytes(fixed(u)?) {
+		0xffff => Ok(None),
+		len => Ok(Some(u.bytes(usize::from(len).min(u.len()))?.to_vec())),
+	}
+}
+
+fn encode_context(ctx: Option<&[u8]>) -> Vec<u8> {
+	match ctx {
+		None => vec![0xff, 0xff],
+		Some(ctx) => [&(ctx.len() as u16).to_le_bytes()[..], ctx].concat(),
+	}
+}
@@ -1,20 +1,25 @@
 use bip39::{Language, Mnemonic};
+use core::{fmt, str::FromStr};
 use nam_tiny_hderive::{bip32::ExtendedPrivKey, Error};
-use qp_rusty_crystals_dilithium::ml_dsa_87::Keypair;
-use rand::{rngs::OsRng, RngCore};
+use qp_rusty_crystals_dilithium::{ml_dsa_87::Keypair, Error as MlDsaError};
+use rand::{CryptoRng, RngCore};
 use rand_chacha::{
 	rand_core::{RngCore as ChaChaCore, SeedableRng},
 	ChaCha20Rng,
 };
-use std::str::FromStr;
+use zeroize::{Zeroize, ZeroizeOnDrop};
 
 #[cfg(test)]
 mod test_vectors;
 #[cfg(test)]
 mod tests;
 
+#[cfg(all(feature = "mlock", target_os = "linux"))]
+mod locked;
 pub mod wormhole;
 
+#[cfg(all(feature = "mlock", target_os = "linux"))]
+pub use locked::LockedHDLattice;
 pub use wormhole::{WormholeError, WormholePair};
 
 #[derive(Debug, thiserror::Error, PartialEq, Eq)]
@@ -35,12 +40,42 @@ pub enum HDLatticeError {
 	InvalidPath(String),
 	#[error("nam-tinyhderive error")]
 	GenericError(Error),
+	#[error("ML-DSA error: {0}")]
+	MlDsa(MlDsaError),
 }
 
-/// Manages entropy generation for HD wallets
+impl From<MlDsaError> for HDLatticeError {
+	fn from(e: MlDsaError) -> Self {
+		HDLatticeError::MlDsa(e)
+	}
+}
+
+/// Manages entropy generation for HD wallets. The seed and master key are overwritten with zeroes
+/// when it is dropped.
 pub struct HDLattice {
-	pub seed: [u8; 64],
-	pub master_key: [u8; 32],
+	seed: [u8; 64],
+	master_key: [u8; 32],
+}
+
+impl Zeroize for HDLattice {
+	fn zeroize(&mut self) {
+		self.seed.zeroize();
+		self.master_key.zeroize();
+	}
+}
+
+impl Drop for HDLattice {
+	fn drop(&mut self) {
+		self.zeroize();
+	}
+}
+
+impl ZeroizeOnDrop for HDLattice {}
+
+impl fmt::Debug for HDLattice {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("HDLattice").finish_non_exhaustive()
+	}
 }
 
 pub const ROOT_PATH: &str = "m";
@@ -51,54 +86,60 @@ pub const QUANTUS_WORMHOLE_CHAIN_ID: &str = "189189189'";
 impl HDLattice {
 	/// Create new HDEntropy from a master seed
 	// #[tarpaulin::skip] // tarpaulin fails - this is covered.
-	pub fn from_seed(seed: [u8; 64]) -> Result<Self, HDLatticeError> {
-		Ok(Self { seed, master_key: Self::master_key_from_seed(&seed)? })
+	pub fn from_seed(mut seed: [u8; 64]) -> Result<Self, HDLatticeError> {
+		let result = Self::from_seed_ref(&seed);
+		seed.zeroize();
+		result
 	}
 
-	/// Create new HDLattice from a BIP39 mnemonic phrase
-	pub fn from_mnemonic(phrase: &str, passphrase: Option<&str>) -> Result<Self, HDLatticeError> {
-		// Parse the mnemonic
-		let mnemonic = Mnemonic::parse_in_normalized(Language::English, phrase)
-			.map_err(|e| HDLatticeError::Bip39Error(e.to_string()))?;
+	fn from_seed_ref(seed: &[u8; 64]) -> Result<Self, HDLatticeError> {
+		Ok(Self { seed: *seed, master_key: Self::master_key_from_seed(seed)? })
+	}
+
+	/// Borrow the BIP39 seed.
+	pub fn seed(&self) -> &[u8; 64] {
+		&self.seed
+	}
 
-		// Generate seed from mnemonic
-		let seed: [u8; 64] = mnemonic.to_seed_normalized(passphrase.unwrap_or(""));
+	/// Borrow the BIP32 master key derived from the seed.
+	pub fn master_key(&self) -> &[u8; 32] {
+		&self.master_key
+	}
 
-		Ok(Self { seed, master_key: Self::master_key_from_seed(&seed)? })
+	/// Create new HDLattice from a BIP39 mnemonic phrase
+	pub fn from_mnemonic(phrase: &str, passphrase: Option<&str>) -> Result<Self, HDLatticeError> {
+		let mut seed = seed_from_mnemonic(phrase, passphrase)?;
+		let result = Self::from_seed_ref(&seed);
+		seed.zeroize();
+		result
 	}
 
 	pub fn master_key_from_seed(seed: &[u8; 64]) -> Result<[u8; 32], HDLatticeError> {
-		let ext = ExtendedPrivKey::derive(seed, "m").unwrap();
+		let ext = ExtendedPrivKey::derive(seed, "m").map_err(HDLatticeError::GenericError)?;
 
 		Ok(ext.secret())
 	}
 
-	pub fn generate_keys(&self) -> Keypair {
-		Keypair::generate(Some(&self.seed))
+	// Wallet keys are expanded with the pre-standard ML-DSA key generation so that addresses
+	// derived by earlier releases stay reachable from the same mnemonic.
+	pub fn generate_keys(&self) -> Result<Keypair, HDLatticeError> {
+		Ok(Keypair::generate_legacy(Some(&self.seed))?)
 	}
 
 	pub fn generate_derived_keys(&self, path: &str) -> Result<Keypair, HDLatticeError> {
-		let derived_entropy = self.derive_entropy(path)?;
-		Ok(Keypair::generate(Some(&derived_entropy)))
+		let mut derived_entropy = self.derive_entropy(path)?;
+		let keys = Keypair::generate_legacy(Some(&derived_entropy));
+		derived_entropy.zeroize();
+		Ok(keys?)
 	}
 
 	pub fn check_path(&self, path: &str) -> Result<(), HDLatticeError> {
-		let p = nam_tiny_hderive::bip44::DerivationPath::from_str(path)
-			.map_err(HDLatticeError::GenericError)?;
-		for element in p.iter() {
-			if !element.is_hardened() {
-				return Err(HDLatticeError::HardenedPathsOnly())
-			}
-		}
-		Ok(())
+		check_hardened(path)
 	}
 
 	/// Derives entropy from a seed along a given path
 	pub fn derive_entropy(&self, path: &str) -> Result<[u8; 32], HDLatticeError> {
-		self.check_path(path)?;
-		let xpriv = ExtendedPrivKey::derive(&self.seed, path)
-			.map_err(|_e| HDLatticeError::KeyDerivationFailed(path.to_string()))?;
-		Ok(xpriv.secret())
+		derive_from_seed(&self.seed, path)
 	}
 
 	/// Generates a wormhole pair from the current entropy state
@@ -111,16 +152,65 @@ impl HDLattice {
 		&self,
 		path: &str,
 	) -> Result<WormholePair, HDLatticeError> {
-		if path.split("/").nth(2) != Some(QUANTUS_WORMHOLE_CHAIN_ID) {
-			return Err(HDLatticeError::InvalidWormholePath(path.to_string()))
+		check_wormhole_path(path)?;
+		let mut entropy = self.derive_entropy(path)?;
+		let pair = WormholePair::generate_pair_from_secret(&entropy);
+		entropy.zeroize();
+		Ok(pair)
+	}
+}
+
+/// Parse a BIP39 mnemonic phrase and stretch it into a seed.
+pub(crate) fn seed_from_mnemonic(
+	phrase: &str,
+	passphrase: Option<&str>,
+) -> Result<[u8; 64], HDLatticeError> {
+	// Parse the mnemonic
+	let mnemonic = Mnemonic::parse_in_normalized(Language::English, phrase)
+		.map_err(|e| HDLatticeError::Bip39Error(e.to_string()))?;
+
+	// Generate seed from mnemonic
+	Ok(mnemonic.to_seed_normalized(passphrase.unwrap_or("")))
+}
+
+pub(crate) fn check_hardened(path: &str) -> Result<(), HDLatticeError> {
+	let p = nam_tiny_hderive::bip44::DerivationPath::from_str(path)
+		.map_err(HDLatticeError::GenericError)?;
+	for element in p.iter() {
+		if !element.is_hardened() {
+			return Err(HDLatticeError::HardenedPathsOnly())
 		}
-		let entropy = self.derive_entropy(path)?;
-		Ok(WormholePair::generate_pair_from_secret(&entropy))
 	}
+	Ok(())
 }
 
-/// Generate a new random mnemonic of the specified word count
+pub(crate) fn check_wormhole_path(path: &str) -> Result<(), HDLatticeError> {
+	if path.split("/").nth(2) != Some(QUANTUS_WORMHOLE_CHAIN_ID) {
+		return Err(HDLatticeError::InvalidWormholePath(path.to_string()))
+	}
+	Ok(())
+}
+
+/// Derives entropy from a seed along a given hardened path
+pub(crate) fn derive_from_seed(seed: &[u8; 64], path: &str) -> Result<[u8; 32], HDLatticeError> {
+	check_hardened(path)?;
+	let xpriv = ExtendedPrivKey::derive(seed, path)
+		.map_err(|_e| HDLatticeError::KeyDerivationFailed(path.to_string()))?;
+	Ok(xpriv.secret())
+}
+
+/// Generate a new random mnemonic of the specified word count (only available with `std`).
+#[cfg(feature = "std")]
 pub fn generate_mnemonic(word_count: usize) -> Result<String, HDLatticeError> {
+	generate_mnemonic_with_rng(word_count, &mut rand::rngs::OsRng)
+}
+
+/// Generate a new mnemonic of the specified word count with entropy drawn from `rng`, e.g. a
+/// hardware TRNG, or a seeded RNG for reproducible tests.
+pub fn generate_mnemonic_with_rng(
+	word_count: usize,
+	rng: &mut (impl RngCore + CryptoRng),
+) -> Result<String, HDLatticeError> {
 	// Calculate entropy bytes needed (12 words = 16 bytes, 24 words = 32 bytes)
 	let bits = match word_count {
 		12 => 128,
@@ -134,12 +224,13 @@ pub fn generate_mnemonic(word_count: usize) -> Result<String, HDLatticeError> {
 	let entropy_bytes = bits / 8;
 	let mut seed = [0u8; 32];
 
-	// Use os rng to make seed
-	OsRng.fill_bytes(&mut seed);
+	// Use the caller's rng to make seed
+	rng.try_fill_bytes(&mut seed)
+		.map_err(|e| HDLatticeError::MnemonicDerivationFailed(e.to_string()))?;
 
 	// Use seed to initiate chacha stream and fill it
-	// NOTE: chacha will "whiten" the entropy provided by the os
-	// if an attacker does not 100% control the os entropy, chacha
+	// NOTE: chacha will "whiten" the entropy provided by the rng
+	// if an attacker does not 100% control the rng, chacha
 	// will provide full entropy, due to avalanche effects
 	let mut chacha_rng = ChaCha20Rng::from_seed(seed);
 
@@ -148,7 +239,10 @@ pub fn generate_mnemonic(word_count: usize) -> Result<String, HDLatticeError> {
 
 	// Create mnemonic from entropy
 	let mnemonic = Mnemonic::from_entropy(&entropy)
-		.map_err(|e| HDLatticeError::MnemonicDerivationFailed(e.to_string()))?;
+		.map_err(|e| HDLatticeError::MnemonicDerivationFailed(e.to_string()));
+	seed.zeroize();
+	entropy.zeroize();
+	let mnemonic = mnemonic?;
 
 	Ok(mnemonic.words().collect::<Vec<&str>>().join(" "))
 }
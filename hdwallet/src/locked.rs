@@ -0,0 +1,150 @@
+//! # Locked HD Wallet
+//!
+//! [LockedHDLattice] is an [HDLattice](crate::HDLattice) whose seed and master key live in
+//! locked, guard-paged memory that is excluded from core dumps (see
+//! [qp_rusty_crystals_dilithium::locked]). Derivation reads the seed in place, and
+//! [LockedHDLattice::generate_derived_keys] writes the ML-DSA secret key straight into locked
+//! storage as well, so a long-running signer never holds them in swappable memory.
+
+use core::fmt;
+
+use qp_rusty_crystals_dilithium::{
+	locked::Locked,
+	ml_dsa_87::{LockedSecretKey, PublicKey},
+};
+use zeroize::Zeroize;
+
+use crate::{
+	check_hardened, check_wormhole_path, derive_from_seed, seed_from_mnemonic, HDLattice,
+	HDLatticeError, WormholePair,
+};
+
+/// HD wallet with its seed and master key in locked memory.
+pub struct LockedHDLattice {
+	seed: Locked<[u8; 64]>,
+	master_key: Locked<[u8; 32]>,
+}
+
+impl LockedHDLattice {
+	/// Create a locked wallet from a master seed; the argument is wiped.
+	pub fn from_seed(mut seed: [u8; 64]) -> Result<Self, HDLatticeError> {
+		let result = Self::from_seed_ref(&seed);
+		seed.zeroize();
+		result
+	}
+
+	fn from_seed_ref(seed: &[u8; 64]) -> Result<Self, HDLatticeError> {
+		let mut master_key = HDLattice::master_key_from_seed(seed)?;
+		let locked = Locked::from_slice(&master_key);
+		master_key.zeroize();
+		Ok(Self { seed: Locked::from_slice(seed)?, master_key: locked? })
+	}
+
+	/// Create a locked wallet from a BIP39 mnemonic phrase
+	pub fn from_mnemonic(phrase: &str, passphrase: Option<&str>) -> Result<Self, HDLatticeError> {
+		let mut seed = seed_from_mnemonic(phrase, passphrase)?;
+		let result = Self::from_seed_ref(&seed);
+		seed.zeroize();
+		result
+	}
+
+	/// Borrow the seed for the duration of `f`.
+	pub fn with_seed<R>(&self, f: impl FnOnce(&[u8; 64]) -> R) -> R {
+		self.seed.with(f)
+	}
+
+	/// Borrow the master key for the duration of `f`.
+	pub fn with_master_key<R>(&self, f: impl FnOnce(&[u8; 32]) -> R) -> R {
+		self.master_key.with(f)
+	}
+
+	/// Keys expanded from the seed with the pre-standard key generation, as
+	/// [HDLattice::generate_keys]; the secret key is kept in locked memory.
+	pub fn generate_keys(&self) -> Result<(LockedSecretKey, PublicKey), HDLatticeError> {
+		Ok(self.seed.with(|seed| LockedSecretKey::generate_legacy(Some(seed)))?)
+	}
+
+	/// Keys derived along `path`, as [HDLattice::generate_derived_keys]; the secret key is kept in
+	/// locked memory.
+	pub fn generate_derived_keys(
+		&self,
+		path: &str,
+	) -> Result<(LockedSecretKey, PublicKey), HDLatticeError> {
+		let mut derived_entropy = self.derive_entropy(path)?;
+		let keys = LockedSecretKey::generate_legacy(Some(&derived_entropy));
+		derived_entropy.zeroize();
+		Ok(keys?)
+	}
+
+	pub fn check_path(&self, path: &str) -> Result<(), HDLatticeError> {
+		check_hardened(path)
+	}
+
+	/// Derives entropy from the locked seed along a given path. The caller should wipe the result.
+	pub fn derive_entropy(&self, path: &str) -> Result<[u8; 32], HDLatticeError> {
+		self.seed.with(|seed| derive_from_seed(seed, path))
+	}
+
+	/// Generates a wormhole pair from the master key
+	pub fn generate_wormhole_pair(&self) -> Result<WormholePair, HDLatticeError> {
+		Ok(self.master_key.with(WormholePair::generate_pair_from_secret))
+	}
+
+	/// Generates a wormhole pair from a specific path
+	pub fn generate_wormhole_pair_from_path(
+		&self,
+		path: &str,
+	) -> Result<WormholePair, HDLatticeError> {
+		check_wormhole_path(path)?;
+		let mut entropy = self.derive_entropy(path)?;
+		let pair = WormholePair::generate_pair_from_secret(&entropy);
+		entropy.zeroize();
+		Ok(pair)
+	}
+}
+
+impl fmt::Debug for LockedHDLattice {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("LockedHDLattice").finish_non_exhaustive()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const MNEMONIC: &str = "rocket primary way job input cactus submit menu zoo burger rent impose";
+
+	#[test]
+	fn matches_unlocked_wallet() {
+		let hd = HDLattice::from_mnemonic(MNEMONIC, None).unwrap();
+		let locked = LockedHDLattice::from_mnemonic(MNEMONIC, None).unwrap();
+		locked.with_seed(|seed| assert_eq!(seed, hd.seed()));
+		locked.with_master_key(|key| assert_eq!(key, hd.master_key()));
+
+		let path = "m/44'/189189'/0'/0'/0'";
+		assert_eq!(locked.derive_entropy(path).unwrap(), hd.derive_entropy(path).unwrap());
+		assert_eq!(locked.derive_entropy("m/44'/0"), Err(HDLatticeError::HardenedPathsOnly()));
+
+		let keys = hd.generate_derived_keys(path).unwrap();
+		let (secret, public) = locked.generate_derived_keys(path).unwrap();
+		assert_eq!(public, keys.public);
+		let sig = secret.sign(b"msg", None, false).unwrap();
+		assert_eq!(sig, keys.sign(b"msg", None, false).unwrap());
+
+		let (secret, public) = locked.generate_keys().unwrap();
+		assert_eq!(public, hd.generate_keys().unwrap().public);
+		assert!(public.verify(b"msg", &secret.sign(b"msg", None, true).unwrap(), None).is_ok());
+
+		let path = "m/44'/189189189'/0'/0'/0'";
+		assert_eq!(
+			locked.generate_wormhole_pair_from_path(path).unwrap().address,
+			hd.generate_wormhole_pair_from_path(path).unwrap().address
+		);
+		assert_eq!(
+			locked.generate_wormhole_pair().unwrap().address,
+			hd.generate_wormhole_pair().unwrap().address
+		);
+		assert_eq!(format!("{locked:?}"), "LockedHDLattice { .. }");
+	}
+}
@@ -7,10 +7,23 @@ pub struct TestVector {
 	pub(crate) private_key: String,
 }
 
+/// Drop `value` in place and return the memory it occupied, to check that a type wipes its
+/// secrets when dropped. Only for types without padding, whose bytes are all initialized.
+pub(crate) fn memory_after_drop<T>(value: T) -> Vec<u8> {
+	let mut slot = core::mem::MaybeUninit::new(value);
+	// SAFETY: the slot is initialized and dropped exactly once; the bytes read back afterwards
+	// stay initialized, since dropping does not deallocate inline storage.
+	unsafe {
+		slot.assume_init_drop();
+		core::slice::from_raw_parts(slot.as_ptr() as *const u8, core::mem::size_of::<T>()).to_vec()
+	}
+}
+
 #[cfg(test)]
 mod hdwallet_tests {
+	use super::memory_after_drop;
 	use crate::{
-		generate_mnemonic,
+		generate_mnemonic, generate_mnemonic_with_rng,
 		test_vectors::{
 			get_test_vectors, load_known_private_keys, str_to_32_bytes, str_to_64_bytes,
 		},
@@ -25,9 +38,9 @@ mod hdwallet_tests {
 	fn test_from_seed() {
 		let mnemonic = "rocket primary way job input cactus submit menu zoo burger rent impose";
 		let hd1 = HDLattice::from_mnemonic(mnemonic, None).unwrap();
-		let hd2 = HDLattice::from_seed(hd1.seed).unwrap();
-		assert_eq!(hd1.master_key, hd2.master_key);
-		assert_eq!(hd1.seed, hd2.seed);
+		let hd2 = HDLattice::from_seed(*hd1.seed()).unwrap();
+		assert_eq!(hd1.master_key(), hd2.master_key());
+		assert_eq!(hd1.seed(), hd2.seed());
 	}
 
 	#[test]
@@ -40,20 +53,28 @@ mod hdwallet_tests {
 		let hd = HDLattice::from_mnemonic(&mnemonic, None).unwrap();
 		let hd2 = HDLattice::from_mnemonic(&mnemonic, None).unwrap();
 		let hd3 = HDLattice::from_mnemonic(&mnemonic, Some("password")).unwrap();
-		// println!("Generated hd: {:?}", hd.master_key);
+		// println!("Generated hd: {:?}", hd.master_key());
 
 		// Derive some child seeds
-		let master_key = hd.generate_keys();
-		let key2 = hd2.generate_keys();
-		let key3 = hd3.generate_keys();
+		let master_key = hd.generate_keys().unwrap();
+		let key2 = hd2.generate_keys().unwrap();
+		let key3 = hd3.generate_keys().unwrap();
 		// println!("Generated key: {:?}", master_key.public.to_bytes());
 
 		// // Seeds should be different but deterministic
-		assert_ne!(master_key.secret.bytes, key3.secret.bytes, "password has no effect");
-		assert_eq!(master_key.secret.bytes, key2.secret.bytes, "keys are not deterministic");
+		assert_ne!(master_key.secret.as_bytes(), key3.secret.as_bytes(), "password has no effect");
+		assert_eq!(
+			master_key.secret.as_bytes(),
+			key2.secret.as_bytes(),
+			"keys are not deterministic"
+		);
 
 		let derived_key = hd.generate_derived_keys("m/0'/2147483647'/1'").unwrap();
-		assert_ne!(master_key.secret.bytes, derived_key.secret.bytes, "derived key not derived");
+		assert_ne!(
+			master_key.secret.as_bytes(),
+			derived_key.secret.as_bytes(),
+			"derived key not derived"
+		);
 
 		// // UNCOMMENT THIS AND RUN WITH `cargo test -- --nocapture` TO GENERATE TEST VECTORS
 		// let vecs = generate_test_vectors(10);
@@ -114,20 +135,22 @@ mod hdwallet_tests {
 			// println!("Deriving seed for path: {}", derivation_path);
 			// Generate keys based on the derivation path
 			let generated_keys = if derivation_path.is_empty() {
-				hd.generate_keys()
+				hd.generate_keys().unwrap()
 			} else {
 				hd.generate_derived_keys(derivation_path).unwrap()
 			};
 
 			// Compare secret keys
 			assert_eq!(
-				generated_keys.secret.bytes, expected_keys.secret.bytes,
+				generated_keys.secret.as_bytes(),
+				expected_keys.secret.as_bytes(),
 				"Secret key mismatch for path: {derivation_path}"
 			);
 
 			// Compare public keys
 			assert_eq!(
-				generated_keys.public.bytes, expected_keys.public.bytes,
+				generated_keys.public.as_bytes(),
+				expected_keys.public.as_bytes(),
 				"Public key mismatch for path: {derivation_path}"
 			);
 		}
@@ -151,6 +174,24 @@ mod hdwallet_tests {
 		}
 	}
 
+	#[test]
+	fn test_generate_mnemonic_with_seeded_rng() {
+		use rand::{rngs::StdRng, SeedableRng};
+
+		let mnemonic = generate_mnemonic_with_rng(24, &mut StdRng::seed_from_u64(7)).unwrap();
+		assert_eq!(mnemonic.split_whitespace().count(), 24);
+		assert_eq!(
+			mnemonic,
+			generate_mnemonic_with_rng(24, &mut StdRng::seed_from_u64(7)).unwrap()
+		);
+		assert_ne!(
+			mnemonic,
+			generate_mnemonic_with_rng(24, &mut StdRng::seed_from_u64(8)).unwrap()
+		);
+		assert!(HDLattice::from_mnemonic(&mnemonic, None).is_ok());
+		assert!(generate_mnemonic_with_rng(13, &mut StdRng::seed_from_u64(7)).is_err());
+	}
+
 	#[test]
 	fn test_generate_mnemonic_invalid_length() {
 		let invalid_lengths = [10, 14, 19, 25]; // Invalid word counts not allowed by BIP-39
@@ -232,7 +273,8 @@ mod hdwallet_tests {
 		let derived_key_0 = hd.derive_entropy("m").unwrap();
 
 		assert_eq!(
-			hd.master_key, derived_key_0,
+			*hd.master_key(),
+			derived_key_0,
 			"Derived key from empty path should match the master seed"
 		);
 	}
@@ -272,8 +314,8 @@ mod hdwallet_tests {
 	fn test_master_key_from_seed() {
 		let mnemonic = "rocket primary way job input cactus submit menu zoo burger rent impose";
 		let hd = HDLattice::from_mnemonic(mnemonic, None).unwrap();
-		let master = HDLattice::master_key_from_seed(&hd.seed).unwrap();
-		assert_eq!(master, hd.master_key, "Master key from seed should match the master key");
+		let master = HDLattice::master_key_from_seed(hd.seed()).unwrap();
+		assert_eq!(&master, hd.master_key(), "Master key from seed should match the master key");
 	}
 
 	#[test]
@@ -292,4 +334,15 @@ mod hdwallet_tests {
 			);
 		}
 	}
+
+	#[test]
+	fn test_wiped_on_drop() {
+		let mnemonic = "rocket primary way job input cactus submit menu zoo burger rent impose";
+		let hd = HDLattice::from_mnemonic(mnemonic, None).unwrap();
+		assert!(hd.seed().iter().any(|&b| b != 0));
+		assert_eq!(format!("{hd:?}"), "HDLattice { .. }");
+
+		let memory = memory_after_drop(hd);
+		assert!(memory.iter().all(|&b| b == 0));
+	}
 }
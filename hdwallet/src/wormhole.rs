@@ -26,10 +26,12 @@
 //! The wormhole addresses provide an additional layer of privacy and security by using
 //! Poseidon hashing, which is particularly well-suited for zero-knowledge proof systems.
 
+use core::fmt;
 use qp_poseidon::{
 	digest_bytes_to_felts, injective_bytes_to_felts, injective_string_to_felts, PoseidonHasher,
 };
 use sp_core::{Hasher, H256};
+use zeroize::{Zeroize, ZeroizeOnDrop};
 
 /// Salt used when deriving wormhole addresses.
 pub const ADDRESS_SALT: &str = "wormhole";
@@ -42,6 +44,8 @@ pub enum WormholeError {
 }
 
 /// A struct representing a wormhole identity pair: address + secret.
+///
+/// The secret is overwritten with zeroes when the pair is dropped.
 #[derive(Clone, Eq, PartialEq)]
 pub struct WormholePair {
 	/// Deterministic Poseidon-derived address.
@@ -49,10 +53,38 @@ pub struct WormholePair {
 	/// First hash of secret
 	pub first_hash: H256,
 	/// The hashed secret used to generate this address.
-	pub secret: [u8; 32],
+	secret: [u8; 32],
+}
+
+impl Zeroize for WormholePair {
+	fn zeroize(&mut self) {
+		self.secret.zeroize();
+	}
+}
+
+impl Drop for WormholePair {
+	fn drop(&mut self) {
+		self.zeroize();
+	}
+}
+
+impl ZeroizeOnDrop for WormholePair {}
+
+impl fmt::Debug for WormholePair {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("WormholePair")
+			.field("address", &self.address)
+			.field("first_hash", &self.first_hash)
+			.finish_non_exhaustive()
+	}
 }
 
 impl WormholePair {
+	/// Borrow the hashed secret used to generate this address.
+	pub fn secret(&self) -> &[u8; 32] {
+		&self.secret
+	}
+
 	/// Generates a new `WormholePair` using secure system entropy (only available with `std`).
 	///
 	/// # Errors
@@ -66,9 +98,12 @@ impl WormholePair {
 			.try_fill_bytes(&mut random_bytes)
 			.map_err(|_| WormholeError::InvalidSecretFormat)?;
 
-		let secret = PoseidonHasher::hash(&random_bytes);
+		let mut secret = PoseidonHasher::hash(&random_bytes).0;
+		random_bytes.zeroize();
 
-		Ok(Self::generate_pair_from_secret(&secret.0))
+		let pair = Self::generate_pair_from_secret(&secret);
+		secret.zeroize();
+		Ok(pair)
 	}
 
 	/// Verifies whether the given raw secret generates the specified wormhole address.
@@ -118,7 +153,7 @@ mod tests {
 		let pair = WormholePair::generate_pair_from_secret(&secret);
 
 		// Assert
-		assert_eq!(pair.secret, secret);
+		assert_eq!(pair.secret(), &secret);
 
 		// We can't easily predict the exact hash output without mocking PoseidonHasher,
 		// but we can verify that it's not zero and that it's deterministic
@@ -167,7 +202,7 @@ mod tests {
 
 		// Assert
 		// 1. Verify that the secret is stored correctly
-		assert_eq!(pair.secret, secret);
+		assert_eq!(pair.secret(), &secret);
 
 		// 2. Verify that the derived address is consistent with our verification method
 		assert!(WormholePair::verify(pair.address, &secret));
@@ -218,13 +253,13 @@ mod tests {
 		let pair = result.unwrap();
 
 		// The secret should not be all zeros
-		assert_ne!(pair.secret, [0u8; 32]);
+		assert_ne!(pair.secret(), &[0u8; 32]);
 
 		// Address should not be zero
 		assert_ne!(pair.address, H256::zero());
 
 		// Verification should work with the generated secret
-		let verification = WormholePair::verify(pair.address, &pair.secret);
+		let verification = WormholePair::verify(pair.address, pair.secret());
 		assert!(verification);
 	}
 
@@ -251,4 +286,18 @@ mod tests {
 		// Assert
 		assert_ne!(pair_with_salt.address, address_with_different_salt);
 	}
+
+	#[test]
+	fn test_secret_wiped_on_drop() {
+		let secret = [42u8; 32];
+		let pair = WormholePair::generate_pair_from_secret(&secret);
+		let address = pair.address;
+		assert!(!format!("{pair:?}").contains("42, 42"));
+
+		let memory = crate::tests::memory_after_drop(pair);
+		let offset = core::mem::offset_of!(WormholePair, secret);
+		assert_eq!(memory[offset..offset + 32], [0u8; 32]);
+		let offset = core::mem::offset_of!(WormholePair, address);
+		assert_eq!(memory[offset..offset + 32], address.0);
+	}
 }
@@ -0,0 +1,190 @@
+// tests/acvp_integration_test.rs
+
+mod helpers;
+
+use helpers::acvp::{
+	hash_message_prime, load, message_prime, KeyGenGroup, PreHash, SigGenGroup, SigVerGroup,
+	SignatureInterface,
+};
+use qp_rusty_crystals_dilithium::{params, sign, Error};
+
+type KeypairFn = fn(&mut [u8], &mut [u8], Option<&[u8]>) -> Result<(), Error>;
+type SignFn = fn(&mut [u8], &[u8], &[u8], &[u8]) -> Result<(), Error>;
+type SignInternalFn = fn(&mut [u8], &[u8], &[u8], &[u8; params::SEEDBYTES]) -> Result<(), Error>;
+type VerifyFn = fn(&[u8], &[u8], &[u8]) -> Result<(), Error>;
+
+/// The raw ML-DSA functions of one parameter set, selected by its ACVP name.
+struct ParameterSet {
+	public_key_bytes: usize,
+	secret_key_bytes: usize,
+	sign_bytes: usize,
+	keypair: KeypairFn,
+	sign_internal: SignInternalFn,
+	sign_mu_with_rnd: SignFn,
+	verify_internal: VerifyFn,
+	verify_mu: VerifyFn,
+}
+
+fn parameter_set(name: &str) -> ParameterSet {
+	match name {
+		"ML-DSA-44" => ParameterSet {
+			public_key_bytes: params::ml_dsa_44::PUBLICKEYBYTES,
+			secret_key_bytes: params::ml_dsa_44::SECRETKEYBYTES,
+			sign_bytes: params::ml_dsa_44::SIGNBYTES,
+			keypair: sign::ml_dsa_44::keypair,
+			sign_internal: sign::ml_dsa_44::sign_internal,
+			sign_mu_with_rnd: sign::ml_dsa_44::sign_mu_with_rnd,
+			verify_internal: sign::ml_dsa_44::verify_internal,
+			verify_mu: sign::ml_dsa_44::verify_mu,
+		},
+		"ML-DSA-65" => ParameterSet {
+			public_key_bytes: params::ml_dsa_65::PUBLICKEYBYTES,
+			secret_key_bytes: params::ml_dsa_65::SECRETKEYBYTES,
+			sign_bytes: params::ml_dsa_65::SIGNBYTES,
+			keypair: sign::ml_dsa_65::keypair,
+			sign_internal: sign::ml_dsa_65::sign_internal,
+			sign_mu_with_rnd: sign::ml_dsa_65::sign_mu_with_rnd,
+			verify_internal: sign::ml_dsa_65::verify_internal,
+			verify_mu: sign::ml_dsa_65::verify_mu,
+		},
+		"ML-DSA-87" => ParameterSet {
+			public_key_bytes: params::ml_dsa_87::PUBLICKEYBYTES,
+			secret_key_bytes: params::ml_dsa_87::SECRETKEYBYTES,
+			sign_bytes: params::ml_dsa_87::SIGNBYTES,
+			keypair: sign::ml_dsa_87::keypair,
+			sign_internal: sign::ml_dsa_87::sign_internal,
+			sign_mu_with_rnd: sign::ml_dsa_87::sign_mu_with_rnd,
+			verify_internal: sign::ml_dsa_87::verify_internal,
+			verify_mu: sign::ml_dsa_87::verify_mu,
+		},
+		_ => panic!("unknown parameter set {name}"),
+	}
+}
+
+/// Whether a group signs mu directly; anything else goes through [m_prime].
+fn external_mu(interface: SignatureInterface, pre_hash: PreHash, external_mu: bool) -> bool {
+	(interface, pre_hash, external_mu) == (SignatureInterface::Internal, PreHash::None, true)
+}
+
+/// The M' a test's message is signed as, for the interface and pre-hash mode of its group.
+/// Combinations this harness cannot drive fail the test rather than being skipped.
+fn m_prime(
+	group: &str,
+	interface: SignatureInterface,
+	pre_hash: PreHash,
+	external_mu: bool,
+	ctx: &[u8],
+	msg: &[u8],
+	hash_alg: Option<&str>,
+) -> Vec<u8> {
+	match (interface, pre_hash, external_mu) {
+		(SignatureInterface::Internal, PreHash::None, false) => msg.to_vec(),
+		(SignatureInterface::External, PreHash::Pure, false) => message_prime(ctx, msg),
+		(SignatureInterface::External, PreHash::Hashed, false) =>
+			hash_message_prime(ctx, msg, hash_alg.expect("preHash test without hashAlg")),
+		other => panic!("{group}: cannot drive {other:?}"),
+	}
+}
+
+#[test]
+fn test_acvp_keygen() {
+	let set = load::<KeyGenGroup>(include_str!("../test_vectors/acvp/ML-DSA-keyGen-FIPS204.json"));
+	let mut count = 0;
+	for group in &set.test_groups {
+		let ps = parameter_set(&group.parameter_set);
+		for test in &group.tests {
+			let mut pk = vec![0u8; ps.public_key_bytes];
+			let mut sk = vec![0u8; ps.secret_key_bytes];
+			(ps.keypair)(&mut pk, &mut sk, Some(&test.seed)).unwrap();
+			assert_eq!(
+				pk, test.pk,
+				"{} keyGen tcId {}: pk mismatch",
+				group.parameter_set, test.tc_id
+			);
+			assert_eq!(
+				sk, test.sk,
+				"{} keyGen tcId {}: sk mismatch",
+				group.parameter_set, test.tc_id
+			);
+			count += 1;
+		}
+	}
+	assert!(count > 0);
+}
+
+#[test]
+fn test_acvp_siggen() {
+	let set = load::<SigGenGroup>(include_str!("../test_vectors/acvp/ML-DSA-sigGen-FIPS204.json"));
+	let mut count = 0;
+	for group in &set.test_groups {
+		let ps = parameter_set(&group.parameter_set);
+		for test in &group.tests {
+			let rnd: [u8; params::SEEDBYTES] = match (&test.rnd, group.deterministic) {
+				(_, true) => [0u8; params::SEEDBYTES],
+				(Some(rnd), false) => rnd.as_slice().try_into().expect("rnd of the wrong length"),
+				(None, false) => panic!("sigGen tcId {}: hedged test without rnd", test.tc_id),
+			};
+			let mut sig = vec![0u8; ps.sign_bytes];
+			if external_mu(group.signature_interface, group.pre_hash, group.external_mu) {
+				let mu = test.mu.as_ref().expect("externalMu test without mu");
+				(ps.sign_mu_with_rnd)(&mut sig, mu, &test.sk, &rnd).unwrap();
+			} else {
+				let m = m_prime(
+					&format!("sigGen tgId {}", group.tg_id),
+					group.signature_interface,
+					group.pre_hash,
+					group.external_mu,
+					&test.context,
+					&test.message,
+					test.hash_alg.as_deref(),
+				);
+				(ps.sign_internal)(&mut sig, &m, &test.sk, &rnd).unwrap();
+			}
+			assert_eq!(
+				sig, test.signature,
+				"{} sigGen tcId {}: signature mismatch",
+				group.parameter_set, test.tc_id
+			);
+			count += 1;
+		}
+	}
+	assert!(count > 0);
+}
+
+#[test]
+fn test_acvp_sigver() {
+	let set = load::<SigVerGroup>(include_str!("../test_vectors/acvp/ML-DSA-sigVer-FIPS204.json"));
+	let mut count = 0;
+	for group in &set.test_groups {
+		let ps = parameter_set(&group.parameter_set);
+		for test in &group.tests {
+			let pk = test.pk.as_ref().or(group.pk.as_ref()).expect("sigVer test without pk");
+			let result =
+				if external_mu(group.signature_interface, group.pre_hash, group.external_mu) {
+					let mu = test.mu.as_ref().expect("externalMu test without mu");
+					(ps.verify_mu)(&test.signature, mu, pk).is_ok()
+				} else {
+					let m = m_prime(
+						&format!("sigVer tgId {}", group.tg_id),
+						group.signature_interface,
+						group.pre_hash,
+						group.external_mu,
+						&test.context,
+						&test.message,
+						test.hash_alg.as_deref(),
+					);
+					(ps.verify_internal)(&test.signature, &m, pk).is_ok()
+				};
+			assert_eq!(
+				result,
+				test.test_passed,
+				"{} sigVer tcId {} ({}): unexpected result",
+				group.parameter_set,
+				test.tc_id,
+				test.reason.as_deref().unwrap_or("-")
+			);
+			count += 1;
+		}
+	}
+	assert!(count > 0);
+}
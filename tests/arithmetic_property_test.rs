@@ -0,0 +1,236 @@
+// tests/arithmetic_property_test.rs
+
+use proptest::prelude::*;
+use qp_rusty_crystals_dilithium::{ntt, params, poly, poly::Poly, reduce, rounding};
+
+const N: usize = params::N as usize;
+const Q: i32 = params::Q;
+/// 2^32 mod Q, the factor `invntt_tomont` multiplies by.
+const MONT: i64 = (1i64 << 32) % Q as i64;
+
+/// A coefficient in `lo..=hi`, half of the time one of the two ends or its neighbour.
+fn coeff(lo: i32, hi: i32) -> impl Strategy<Value = i32> {
+	prop_oneof![prop::sample::select(vec![lo, lo + 1, hi - 1, hi]), lo..=hi]
+}
+
+/// N coefficients in `lo..=hi`, weighted towards the ends as in `coeff`.
+fn coeffs(lo: i32, hi: i32) -> impl Strategy<Value = Vec<i32>> {
+	prop::collection::vec(coeff(lo, hi), N)
+}
+
+fn bytes(len: usize) -> impl Strategy<Value = Vec<u8>> {
+	prop::collection::vec(any::<u8>(), len)
+}
+
+fn to_poly(coeffs: &[i32]) -> Poly {
+	Poly { coeffs: coeffs.try_into().unwrap() }
+}
+
+fn canonical(coeffs: &[i32]) -> Vec<i64> {
+	coeffs.iter().map(|&c| i64::from(c).rem_euclid(Q as i64)).collect()
+}
+
+/// a * b in Z_Q[X]/(X^N + 1), one coefficient product at a time.
+fn schoolbook(a: &[i32], b: &[i32]) -> Vec<i64> {
+	let mut c = vec![0i64; N];
+	for i in 0..N {
+		for j in 0..N {
+			let product = i64::from(a[i]) * i64::from(b[j]) % Q as i64;
+			if i + j < N {
+				c[i + j] += product;
+			} else {
+				c[i + j - N] -= product;
+			}
+		}
+	}
+	c.iter().map(|c| c.rem_euclid(Q as i64)).collect()
+}
+
+proptest! {
+	#[test]
+	fn ntt_multiplication_matches_schoolbook(
+		a in coeffs(-(Q - 1), Q - 1),
+		b in coeffs(-(Q - 1), Q - 1),
+	) {
+		let (mut a_hat, mut b_hat, mut c) = (to_poly(&a), to_poly(&b), Poly::default());
+		poly::ntt(&mut a_hat);
+		poly::ntt(&mut b_hat);
+		poly::pointwise_montgomery(&mut c, &a_hat, &b_hat);
+		poly::invntt_tomont(&mut c);
+		prop_assert!(c.coeffs.iter().all(|c| c.abs() < Q));
+		prop_assert_eq!(canonical(&c.coeffs), schoolbook(&a, &b));
+	}
+
+	#[test]
+	fn invntt_undoes_ntt_up_to_montgomery_factor(a in coeffs(-(Q - 1), Q - 1)) {
+		let mut a_hat: [i32; N] = a.clone().try_into().unwrap();
+		ntt::ntt(&mut a_hat);
+		a_hat.iter_mut().for_each(|c| *c = reduce::reduce32(*c));
+		ntt::invntt_tomont(&mut a_hat);
+		let expected: Vec<i64> =
+			canonical(&a).iter().map(|c| c * MONT % Q as i64).collect();
+		prop_assert_eq!(canonical(&a_hat), expected);
+	}
+
+	#[test]
+	fn montgomery_reduce_range(
+		a in prop_oneof![
+			Just(-(1i64 << 31) * Q as i64),
+			Just((1i64 << 31) * Q as i64 - 1),
+			-(1i64 << 31) * Q as i64..(1i64 << 31) * Q as i64,
+		]
+	) {
+		let r = reduce::montgomery_reduce(a);
+		prop_assert!(-Q < r && r < Q);
+		prop_assert_eq!(
+			(i128::from(r) << 32).rem_euclid(Q as i128),
+			i128::from(a).rem_euclid(Q as i128)
+		);
+	}
+
+	#[test]
+	fn reduce32_range(
+		// The first input is the one reduced to the lowest representative.
+		a in prop_oneof![Just(-(255 << 23) - (1 << 22)), coeff(i32::MIN, i32::MAX - (1 << 22))]
+	) {
+		let r = reduce::reduce32(a);
+		prop_assert!((-6283009..=6283008).contains(&r));
+		prop_assert_eq!(i64::from(r).rem_euclid(Q as i64), i64::from(a).rem_euclid(Q as i64));
+	}
+
+	#[test]
+	fn caddq_range(a in coeff(-(Q - 1), Q - 1)) {
+		let r = reduce::caddq(a);
+		prop_assert!((0..Q).contains(&r));
+		prop_assert_eq!(r, a.rem_euclid(Q));
+	}
+
+	#[test]
+	fn power2round_splits_at_d(a in coeff(0, Q - 1)) {
+		let (a0, a1) = rounding::power2round(a);
+		prop_assert!(-(1 << (params::D - 1)) < a0 && a0 <= 1 << (params::D - 1));
+		prop_assert_eq!((a1 << params::D) + a0, a);
+	}
+
+	#[test]
+	fn t1_round_trip(t1 in coeffs(0, (1 << 10) - 1), packed in bytes(params::POLYT1_PACKEDBYTES)) {
+		let mut buf = [0u8; params::POLYT1_PACKEDBYTES];
+		let mut r = Poly::default();
+		poly::t1_pack(&mut buf, &to_poly(&t1));
+		poly::t1_unpack(&mut r, &buf);
+		prop_assert_eq!(&r.coeffs[..], &t1[..]);
+
+		poly::t1_unpack(&mut r, &packed);
+		poly::t1_pack(&mut buf, &r);
+		prop_assert_eq!(&buf[..], &packed[..]);
+	}
+
+	#[test]
+	fn t0_round_trip(
+		t0 in coeffs(-(1 << (params::D - 1)) + 1, 1 << (params::D - 1)),
+		packed in bytes(params::POLYT0_PACKEDBYTES),
+	) {
+		let mut buf = [0u8; params::POLYT0_PACKEDBYTES];
+		let mut r = Poly::default();
+		poly::t0_pack(&mut buf, &to_poly(&t0));
+		poly::t0_unpack(&mut r, &buf);
+		prop_assert_eq!(&r.coeffs[..], &t0[..]);
+
+		poly::t0_unpack(&mut r, &packed);
+		poly::t0_pack(&mut buf, &r);
+		prop_assert_eq!(&buf[..], &packed[..]);
+	}
+}
+
+/// Packing and rounding properties for one parameter set, named by its module in `params` and
+/// `poly`.
+macro_rules! parameter_set {
+	($set:ident) => {
+		mod $set {
+			use super::*;
+			use qp_rusty_crystals_dilithium::{params::$set as p, poly::$set as poly};
+
+			const ETA: i32 = p::ETA as i32;
+			const GAMMA1: i32 = p::GAMMA1 as i32;
+			const GAMMA2: i32 = p::GAMMA2 as i32;
+			const ALPHA: i32 = 2 * GAMMA2;
+			/// Number of distinct high parts, (Q - 1) / ALPHA.
+			const M: i32 = (Q - 1) / ALPHA;
+
+			proptest! {
+				#[test]
+				fn eta_round_trip(s in coeffs(-ETA, ETA)) {
+					let mut buf = [0u8; p::POLYETA_PACKEDBYTES];
+					let mut r = Poly::default();
+					poly::eta_pack(&mut buf, &to_poly(&s));
+					poly::eta_unpack(&mut r, &buf);
+					prop_assert_eq!(&r.coeffs[..], &s[..]);
+				}
+
+				#[test]
+				fn z_round_trip(
+					z in coeffs(-(GAMMA1 - 1), GAMMA1),
+					packed in bytes(p::POLYZ_PACKEDBYTES),
+				) {
+					let mut buf = [0u8; p::POLYZ_PACKEDBYTES];
+					let mut r = Poly::default();
+					poly::z_pack(&mut buf, &to_poly(&z));
+					poly::z_unpack(&mut r, &buf);
+					prop_assert_eq!(&r.coeffs[..], &z[..]);
+
+					poly::z_unpack(&mut r, &packed);
+					poly::z_pack(&mut buf, &r);
+					prop_assert_eq!(&buf[..], &packed[..]);
+				}
+
+				#[test]
+				fn decompose_splits_at_alpha(a in coeffs(0, Q - 1)) {
+					// The input goes in the first argument, which keeps the low bits and passes
+					// the high bits out through the second; polyvec::decompose swaps them back.
+					let (mut a0, mut a1) = (to_poly(&a), Poly::default());
+					poly::decompose(&mut a0, &mut a1);
+					for i in 0..N {
+						let (high, low) = (a1.coeffs[i], a0.coeffs[i]);
+						prop_assert!((0..M).contains(&high));
+						prop_assert!(
+							(-GAMMA2 < low && low <= GAMMA2)
+								|| (high == 0 && (-GAMMA2..0).contains(&low))
+						);
+						prop_assert_eq!((high * ALPHA + low).rem_euclid(Q), a[i]);
+					}
+				}
+
+				/// As in signing: `w1` are the high bits of w and `a0` the low bits of w plus the
+				/// perturbation the verifier sees, so the verifier holds `w1 * ALPHA + a0`.
+				#[test]
+				fn use_hint_recovers_high_bits(
+					w1 in coeffs(0, M - 1),
+					a0 in coeffs(-(ALPHA - 1), ALPHA - 1),
+				) {
+					let mut h = Poly::default();
+					let ones = poly::make_hint(&mut h, &to_poly(&a0), &to_poly(&w1));
+					prop_assert_eq!(ones, h.coeffs.iter().sum::<i32>());
+
+					let v: Vec<i32> =
+						(0..N).map(|i| (w1[i] * ALPHA + a0[i]).rem_euclid(Q)).collect();
+					let (mut v0, mut v1) = (to_poly(&v), Poly::default());
+					poly::decompose(&mut v0, &mut v1);
+					for i in 0..N {
+						prop_assert_eq!(h.coeffs[i], i32::from(v1.coeffs[i] != w1[i]));
+					}
+
+					let mut corrected = to_poly(&v);
+					poly::use_hint(&mut corrected, &h);
+					prop_assert_eq!(&corrected.coeffs[..], &w1[..]);
+				}
+			}
+		}
+	};
+}
+
+parameter_set!(ml_dsa_44);
+parameter_set!(ml_dsa_65);
+parameter_set!(ml_dsa_87);
+parameter_set!(lvl2);
+parameter_set!(lvl3);
+parameter_set!(lvl5);
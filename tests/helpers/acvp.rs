@@ -0,0 +1,187 @@
+#![allow(dead_code)]
+
+//! Loader for the ACVP-Server ML-DSA JSON vector sets (keyGen, sigGen and sigVer).
+//!
+//! The structs accept both the original FIPS204 internal-interface files and the later revision
+//! that adds `signatureInterface`, `preHash`, `externalMu`, `context` and `hashAlg`; fields missing
+//! from a file take the value the older revision implied.
+
+use qp_rusty_crystals_dilithium::PH;
+use serde::{Deserialize, Deserializer};
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VectorSet<G> {
+	pub vs_id: u32,
+	pub algorithm: String,
+	pub mode: String,
+	pub revision: String,
+	pub test_groups: Vec<G>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyGenGroup {
+	pub tg_id: u32,
+	pub parameter_set: String,
+	pub tests: Vec<KeyGenTest>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyGenTest {
+	pub tc_id: u32,
+	#[serde(deserialize_with = "from_hex")]
+	pub seed: Vec<u8>,
+	#[serde(deserialize_with = "from_hex")]
+	pub pk: Vec<u8>,
+	#[serde(deserialize_with = "from_hex")]
+	pub sk: Vec<u8>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SigGenGroup {
+	pub tg_id: u32,
+	pub parameter_set: String,
+	pub deterministic: bool,
+	#[serde(default)]
+	pub signature_interface: SignatureInterface,
+	#[serde(default)]
+	pub pre_hash: PreHash,
+	#[serde(default)]
+	pub external_mu: bool,
+	pub tests: Vec<SigGenTest>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SigGenTest {
+	pub tc_id: u32,
+	#[serde(deserialize_with = "from_hex")]
+	pub sk: Vec<u8>,
+	#[serde(default, deserialize_with = "from_hex")]
+	pub message: Vec<u8>,
+	#[serde(default, deserialize_with = "from_hex_opt")]
+	pub mu: Option<Vec<u8>>,
+	#[serde(default, deserialize_with = "from_hex_opt")]
+	pub rnd: Option<Vec<u8>>,
+	#[serde(default, deserialize_with = "from_hex")]
+	pub context: Vec<u8>,
+	pub hash_alg: Option<String>,
+	#[serde(deserialize_with = "from_hex")]
+	pub signature: Vec<u8>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SigVerGroup {
+	pub tg_id: u32,
+	pub parameter_set: String,
+	#[serde(default)]
+	pub signature_interface: SignatureInterface,
+	#[serde(default)]
+	pub pre_hash: PreHash,
+	#[serde(default)]
+	pub external_mu: bool,
+	#[serde(default, deserialize_with = "from_hex_opt")]
+	pub pk: Option<Vec<u8>>,
+	pub tests: Vec<SigVerTest>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SigVerTest {
+	pub tc_id: u32,
+	pub test_passed: bool,
+	#[serde(default, deserialize_with = "from_hex_opt")]
+	pub pk: Option<Vec<u8>>,
+	#[serde(default, deserialize_with = "from_hex")]
+	pub message: Vec<u8>,
+	#[serde(default, deserialize_with = "from_hex_opt")]
+	pub mu: Option<Vec<u8>>,
+	#[serde(default, deserialize_with = "from_hex")]
+	pub context: Vec<u8>,
+	pub hash_alg: Option<String>,
+	#[serde(deserialize_with = "from_hex")]
+	pub signature: Vec<u8>,
+	pub reason: Option<String>,
+}
+
+/// Which ML-DSA entry point a group targets. Files predating the field only test the internal
+/// functions.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SignatureInterface {
+	#[default]
+	Internal,
+	External,
+}
+
+/// How an external-interface group treats the message. Internal-interface groups say `none`, as
+/// do files predating the field.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PreHash {
+	#[default]
+	None,
+	Pure,
+	#[serde(rename = "preHash")]
+	Hashed,
+}
+
+pub fn load<G: for<'de> Deserialize<'de>>(json: &str) -> VectorSet<G> {
+	serde_json::from_str(json).expect("malformed ACVP vector set")
+}
+
+/// Build M' = 0 || |ctx| || ctx || M as ML-DSA.Sign and ML-DSA.Verify do for pure signatures.
+pub fn message_prime(ctx: &[u8], msg: &[u8]) -> Vec<u8> {
+	let mut m = Vec::with_capacity(2 + ctx.len() + msg.len());
+	m.push(0);
+	m.push(ctx.len() as u8);
+	m.extend_from_slice(ctx);
+	m.extend_from_slice(msg);
+	m
+}
+
+/// Build M' = 1 || |ctx| || ctx || OID || PH(M) as HashML-DSA.Sign and HashML-DSA.Verify do.
+pub fn hash_message_prime(ctx: &[u8], msg: &[u8], hash_alg: &str) -> Vec<u8> {
+	let ph = pre_hash_function(hash_alg);
+	let mut digest = [0u8; PH::MAX_DIGEST_BYTES];
+	let n = ph.digest(msg, &mut digest);
+	let mut m = Vec::with_capacity(2 + ctx.len() + 11 + n);
+	m.push(1);
+	m.push(ctx.len() as u8);
+	m.extend_from_slice(ctx);
+	m.extend_from_slice(&ph.oid());
+	m.extend_from_slice(&digest[..n]);
+	m
+}
+
+/// The pre-hash function named by a test's `hashAlg`.
+pub fn pre_hash_function(hash_alg: &str) -> PH {
+	match hash_alg {
+		"SHA2-224" => PH::SHA224,
+		"SHA2-256" => PH::SHA256,
+		"SHA2-384" => PH::SHA384,
+		"SHA2-512" => PH::SHA512,
+		"SHA2-512/224" => PH::SHA512_224,
+		"SHA2-512/256" => PH::SHA512_256,
+		"SHA3-224" => PH::SHA3_224,
+		"SHA3-256" => PH::SHA3_256,
+		"SHA3-384" => PH::SHA3_384,
+		"SHA3-512" => PH::SHA3_512,
+		"SHAKE-128" => PH::SHAKE128,
+		"SHAKE-256" => PH::SHAKE256,
+		_ => panic!("unknown hashAlg {hash_alg}"),
+	}
+}
+
+fn from_hex<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+	let s = String::deserialize(deserializer)?;
+	hex::decode(s).map_err(serde::de::Error::custom)
+}
+
+fn from_hex_opt<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Vec<u8>>, D::Error> {
+	from_hex(deserializer).map(Some)
+}
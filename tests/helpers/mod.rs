@@ -1 +1,3 @@
+pub mod acvp;
 pub mod kat;
+pub mod wycheproof;
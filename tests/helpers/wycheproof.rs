@@ -0,0 +1,88 @@
+#![allow(dead_code)]
+
+//! Reader and writer for the crafted ML-DSA verification vectors in
+//! `test_vectors/wycheproof`, laid out like the Wycheproof project's test files: groups of tests
+//! sharing a public key, each test naming its expected result and the flags that explain it.
+//!
+//! Two fields go beyond the Wycheproof schema. `msgRepeat` repeats `msg` to keep long messages
+//! out of the file, and `error` names the [qp_rusty_crystals_dilithium::Error] variant an
+//! invalid test must fail with.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VectorFile {
+	pub algorithm: String,
+	pub number_of_tests: usize,
+	pub header: Vec<String>,
+	pub notes: BTreeMap<String, String>,
+	pub test_groups: Vec<TestGroup>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TestGroup {
+	#[serde(rename = "type")]
+	pub kind: String,
+	pub comment: String,
+	#[serde(serialize_with = "to_hex", deserialize_with = "from_hex")]
+	pub public_key: Vec<u8>,
+	pub tests: Vec<TestCase>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TestCase {
+	pub tc_id: usize,
+	pub comment: String,
+	pub flags: Vec<String>,
+	#[serde(serialize_with = "to_hex", deserialize_with = "from_hex")]
+	pub msg: Vec<u8>,
+	#[serde(default = "one", skip_serializing_if = "is_one")]
+	pub msg_repeat: usize,
+	#[serde(serialize_with = "to_hex", deserialize_with = "from_hex")]
+	pub ctx: Vec<u8>,
+	#[serde(serialize_with = "to_hex", deserialize_with = "from_hex")]
+	pub sig: Vec<u8>,
+	pub result: Outcome,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub error: Option<String>,
+}
+
+impl TestCase {
+	/// The message, with `msg` repeated `msgRepeat` times.
+	pub fn message(&self) -> Vec<u8> {
+		self.msg.repeat(self.msg_repeat)
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Outcome {
+	Valid,
+	Invalid,
+}
+
+pub fn load(json: &str) -> VectorFile {
+	serde_json::from_str(json).expect("malformed Wycheproof vector file")
+}
+
+fn one() -> usize {
+	1
+}
+
+fn is_one(n: &usize) -> bool {
+	*n == 1
+}
+
+fn to_hex<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+	serializer.serialize_str(&hex::encode(bytes))
+}
+
+fn from_hex<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+	let s = String::deserialize(deserializer)?;
+	hex::decode(s).map_err(serde::de::Error::custom)
+}
@@ -0,0 +1,73 @@
+// tests/low_stack_integration_test.rs
+
+use std::{hint::black_box, ptr, thread};
+
+use qp_rusty_crystals_dilithium::{ml_dsa_87::Keypair, params::SEEDBYTES};
+
+/// How far below the caller's frame the stack is painted and inspected.
+const PAINTED: usize = 512 * 1024;
+/// Left alone just below the caller's frame, for the painting and measuring calls themselves.
+const SLACK: usize = 4 * 1024;
+const PATTERN: u64 = 0x5a5a_a5a5_5a5a_a5a5;
+
+/// Fill the stack below the current frame with [PATTERN], returning the address it starts below.
+#[inline(never)]
+fn paint() -> usize {
+	let marker = 0u8;
+	let top = (black_box(&marker) as *const u8 as usize - SLACK) & !7;
+	for addr in (top - PAINTED..top).step_by(8) {
+		// SAFETY: the thread was spawned with a stack well beyond PAINTED + SLACK, and this
+		// memory lies below every live frame.
+		unsafe { ptr::write_volatile(addr as *mut u64, PATTERN) };
+	}
+	top
+}
+
+/// How many bytes below `top` were overwritten since [paint].
+#[inline(never)]
+fn used_below(top: usize) -> usize {
+	let deepest = (top - PAINTED..top)
+		.step_by(8)
+		// SAFETY: as in paint; the frames that ran in between have all returned.
+		.find(|&addr| unsafe { ptr::read_volatile(addr as *const u64) } != PATTERN)
+		.unwrap_or(top);
+	top - deepest
+}
+
+/// Peak stack used by `f`, run on a fresh thread.
+fn peak_stack(f: fn(&Keypair)) -> usize {
+	let keys = Keypair::generate(Some(&[9u8; SEEDBYTES])).unwrap();
+	thread::Builder::new()
+		.stack_size(2 * PAINTED)
+		.spawn(move || {
+			let top = paint();
+			f(black_box(&keys));
+			used_below(top)
+		})
+		.unwrap()
+		.join()
+		.unwrap()
+}
+
+fn sign(keys: &Keypair) {
+	black_box(keys.sign(b"stack", None, false).unwrap());
+}
+
+fn sign_low_memory(keys: &Keypair) {
+	black_box(keys.sign_low_memory(b"stack", None, false).unwrap());
+}
+
+#[test]
+fn low_memory_signing_uses_a_fraction_of_the_stack() {
+	let expanded = peak_stack(sign);
+	let low_memory = peak_stack(sign_low_memory);
+	println!("ML-DSA-87 peak stack: sign {expanded} bytes, sign_low_memory {low_memory} bytes");
+
+	// The expanded key alone is K * L + L + 2K = 79 polynomials of 1 KiB.
+	assert!(expanded > 79 * 1024, "measured {expanded} bytes for sign");
+	assert!(low_memory * 2 < expanded, "measured {low_memory} bytes for sign_low_memory");
+	if !cfg!(debug_assertions) {
+		// z, w1 and w0 are L + 2K = 23 polynomials; the rest is hashing state and small buffers.
+		assert!(low_memory < 48 * 1024, "measured {low_memory} bytes for sign_low_memory");
+	}
+}
@@ -0,0 +1,68 @@
+// tests/no_heap_integration_test.rs
+
+use std::{
+	alloc::{GlobalAlloc, Layout, System},
+	cell::Cell,
+};
+
+use qp_rusty_crystals_dilithium::{ml_dsa_44, ml_dsa_65, ml_dsa_87, params::SEEDBYTES, PH};
+
+/// Counts the allocations made on the current thread, so that the test harness running on other
+/// threads does not disturb the count.
+struct CountingAllocator;
+
+thread_local! {
+	static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+	unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+		let _ = ALLOCATIONS.try_with(|n| n.set(n.get() + 1));
+		System.alloc(layout)
+	}
+
+	unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+		System.dealloc(ptr, layout)
+	}
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn allocations_in(f: impl FnOnce()) -> usize {
+	let before = ALLOCATIONS.with(Cell::get);
+	f();
+	ALLOCATIONS.with(Cell::get) - before
+}
+
+macro_rules! no_heap_test {
+	($name:ident, $level:ident) => {
+		#[test]
+		fn $name() {
+			let msg = [7u8; 1000];
+			let ctx = Some(&b"ctx"[..]);
+			let mut digest = [0u8; PH::MAX_DIGEST_BYTES];
+			let n = PH::SHA512.digest(&msg, &mut digest);
+
+			let count = allocations_in(|| {
+				let keys = $level::Keypair::generate(Some(&[1u8; SEEDBYTES])).unwrap();
+				let sig = keys.sign(&msg, ctx, false).unwrap();
+				keys.verify(&msg, &sig, ctx).unwrap();
+				let mu = keys.public.message_representative(&msg, ctx).unwrap();
+				keys.public.verify_mu(&mu, &sig).unwrap();
+				let prepared = keys.secret.prepare();
+				assert_eq!(prepared.sign(&msg, ctx, false).unwrap(), sig);
+				keys.public.prepare().verify(&msg, &sig, ctx).unwrap();
+
+				let sig = keys.prehash_sign(&msg, ctx, false, PH::SHA512).unwrap();
+				keys.prehash_verify(&msg, &sig, ctx, PH::SHA512).unwrap();
+				keys.verify_prehashed(&digest[..n], &sig, ctx, PH::SHA512).unwrap();
+			});
+			assert_eq!(count, 0);
+		}
+	};
+}
+
+no_heap_test!(ml_dsa_44_never_allocates, ml_dsa_44);
+no_heap_test!(ml_dsa_65_never_allocates, ml_dsa_65);
+no_heap_test!(ml_dsa_87_never_allocates, ml_dsa_87);
@@ -9,16 +9,16 @@ fn test_sign() {
 	let hd_lattice = HDLattice::from_mnemonic(&mnemonic, None)
 		.expect("Failed to create HDLattice from mnemonic");
 
-	let dilithium_keypair = hd_lattice.generate_keys();
+	let dilithium_keypair = hd_lattice.generate_keys().expect("Failed to generate keys");
 
 	// Step 2: Define the message to sign
 	let message = b"Hello, Dilithium!";
 
 	// Step 3: Sign the message using the secret key
-	let signature = dilithium_keypair.sign(message, None, false);
+	let signature = dilithium_keypair.sign(message, None, false).expect("Failed to sign");
 
 	// Step 4: Verify the signature using the public key
 	let verify_result = dilithium_keypair.verify(message, &signature, None);
 
-	assert!(verify_result, "Signature verification failed",);
+	assert!(verify_result.is_ok(), "Signature verification failed: {verify_result:?}");
 }
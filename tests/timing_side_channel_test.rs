@@ -0,0 +1,345 @@
+// tests/timing_side_channel_test.rs
+//
+// A dudect-style check for data-dependent timing: each routine is timed on a fixed input and on
+// fresh random inputs, interleaved at random, and Welch's t-test compares the two classes. The
+// routines sample by rejection, so inputs are chosen to reject the same way in both classes;
+// what is left to differ is the secret data a constant-time routine must not branch on.
+//
+// Timings only mean something in an optimized build, so this target does nothing in debug
+// builds. Run it with
+//
+//     cargo test --release --test timing_side_channel_test [-- <name filter>...]
+
+use std::{hint::black_box, process::ExitCode, time::Instant};
+
+use qp_rusty_crystals_dilithium::{
+	fips202, params, poly::Poly, MlDsa44, MlDsa65, MlDsa87, ParameterSet, SecretKey,
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+const N: usize = params::N as usize;
+const Q: i32 = params::Q;
+
+/// |t| above which a routine fails. dudect reads 4.5 as a likely leak and 10 as a definite one;
+/// the higher bound keeps shared CI machines from failing on noise.
+const THRESHOLD: f64 = 10.0;
+/// Inputs are prepared this many at a time, outside the timed region.
+const BATCH: usize = 1000;
+/// A routine fails only if this many measurements in a row exceed THRESHOLD, so that a burst of
+/// system noise does not fail it where a real leak shows up every time.
+const ATTEMPTS: usize = 2;
+/// Size of the pools random inputs are drawn from when they are costly to make.
+const POOL: usize = 256;
+
+fn main() -> ExitCode {
+	if cfg!(debug_assertions) {
+		println!(
+			"timing_side_channel_test: skipped in debug builds, run with `cargo test --release \
+			 --test timing_side_channel_test`"
+		);
+		return ExitCode::SUCCESS;
+	}
+
+	let mut report = Report {
+		filter: std::env::args().skip(1).filter(|arg| !arg.starts_with('-')).collect(),
+		failures: Vec::new(),
+	};
+	ml_dsa_44(&mut report);
+	ml_dsa_65(&mut report);
+	ml_dsa_87(&mut report);
+	lvl2(&mut report);
+	lvl3(&mut report);
+	lvl5(&mut report);
+	sign::<MlDsa44>(&mut report, "MlDsa44::sign");
+	sign::<MlDsa65>(&mut report, "MlDsa65::sign");
+	sign::<MlDsa87>(&mut report, "MlDsa87::sign");
+
+	if report.failures.is_empty() {
+		return ExitCode::SUCCESS;
+	}
+	eprintln!("timing depends on secret data: {}", report.failures.join(", "));
+	ExitCode::FAILURE
+}
+
+struct Report {
+	/// Run only routines whose name contains one of these; all of them if empty.
+	filter: Vec<String>,
+	failures: Vec<String>,
+}
+
+impl Report {
+	/// Time `run` `samples` times, `reps` calls per sample, on `fixed` and on inputs from
+	/// `random`, and record a failure if the two classes' timings still differ after ATTEMPTS
+	/// measurements.
+	fn check<I: Clone>(
+		&mut self,
+		name: &str,
+		samples: usize,
+		reps: usize,
+		fixed: I,
+		mut random: impl FnMut(&mut StdRng) -> I,
+		mut run: impl FnMut(&I),
+	) {
+		if !self.filter.is_empty() && !self.filter.iter().any(|f| name.contains(f.as_str())) {
+			return;
+		}
+		for attempt in 0..ATTEMPTS {
+			let times = measure(samples, reps, &fixed, &mut random, &mut run, attempt as u64);
+			let t = max_t(&times);
+			println!(
+				"{name:<28} {:>8} samples  max |t| = {t:>7.2}  {}",
+				times[0].len() + times[1].len(),
+				if t > THRESHOLD { "FAIL" } else { "ok" }
+			);
+			if t <= THRESHOLD {
+				return;
+			}
+		}
+		self.failures.push(name.to_string());
+	}
+}
+
+/// Timings of `run` on `fixed` and on `random` inputs, in random order, by class.
+fn measure<I: Clone>(
+	samples: usize,
+	reps: usize,
+	fixed: &I,
+	random: &mut impl FnMut(&mut StdRng) -> I,
+	run: &mut impl FnMut(&I),
+	attempt: u64,
+) -> [Vec<f64>; 2] {
+	let rng = &mut StdRng::seed_from_u64(SEED + 1 + attempt);
+	let mut times: [Vec<f64>; 2] = Default::default();
+	// The first batch warms caches and branch predictors and is not counted.
+	for batch in 0..=samples.div_ceil(BATCH) {
+		let classes: Vec<usize> = (0..BATCH).map(|_| rng.gen_range(0..2)).collect();
+		let inputs: Vec<I> = classes
+			.iter()
+			.map(|&class| if class == 0 { fixed.clone() } else { random(rng) })
+			.collect();
+		for (&class, input) in classes.iter().zip(&inputs) {
+			let start = Instant::now();
+			for _ in 0..reps {
+				run(black_box(input));
+			}
+			let elapsed = start.elapsed().as_nanos() as f64;
+			if batch > 0 {
+				times[class].push(elapsed);
+			}
+		}
+	}
+	times
+}
+
+/// The fixed inputs, and pools of random ones, come from this seed.
+const SEED: u64 = 0x7469_6d69_6e67;
+
+fn rng() -> StdRng {
+	StdRng::seed_from_u64(SEED)
+}
+
+/// Running mean and variance, by Welford's method.
+#[derive(Default)]
+struct Moments {
+	n: f64,
+	mean: f64,
+	m2: f64,
+}
+
+impl Moments {
+	fn push(&mut self, x: f64) {
+		self.n += 1.0;
+		let delta = x - self.mean;
+		self.mean += delta / self.n;
+		self.m2 += delta * (x - self.mean);
+	}
+
+	fn variance(&self) -> f64 {
+		self.m2 / (self.n - 1.0)
+	}
+}
+
+fn welch_t(a: &Moments, b: &Moments) -> f64 {
+	(a.mean - b.mean) / (a.variance() / a.n + b.variance() / b.n).sqrt()
+}
+
+/// The largest |t| over all timings and over the timings below a few percentiles, which keeps
+/// interrupts and other long outliers from hiding a difference.
+fn max_t(times: &[Vec<f64>; 2]) -> f64 {
+	let mut all: Vec<f64> = times.concat();
+	all.sort_by(f64::total_cmp);
+	[1.0, 0.99, 0.9, 0.75, 0.5]
+		.iter()
+		.map(|&percentile| {
+			let cutoff = all[((all.len() - 1) as f64 * percentile) as usize];
+			let [a, b] = times.each_ref().map(|times| {
+				let mut moments = Moments::default();
+				times.iter().filter(|&&x| x <= cutoff).for_each(|&x| moments.push(x));
+				moments
+			});
+			welch_t(&a, &b).abs()
+		})
+		.fold(0.0, f64::max)
+}
+
+fn small_poly(rng: &mut StdRng, bound: i32) -> Poly {
+	let mut a = Poly::default();
+	a.coeffs.iter_mut().for_each(|c| *c = rng.gen_range(-bound..=bound));
+	a
+}
+
+/// Bytes of the SHAKE256 stream `challenge` reads for `seed`: eight for the signs, then one per
+/// position drawn. Positions larger than the current index are rejected and drawn again, so this
+/// count fixes the number of loop iterations.
+fn challenge_draws(seed: &[u8], tau: usize) -> usize {
+	let mut state = fips202::KeccakState::default();
+	fips202::shake256_absorb(&mut state, seed, seed.len());
+	fips202::shake256_finalize(&mut state);
+	let mut buf = [0u8; fips202::SHAKE256_RATE];
+	fips202::shake256_squeezeblocks(&mut buf, 1, &mut state);
+	let mut draws = 8;
+	for i in N - tau..N {
+		loop {
+			if draws % fips202::SHAKE256_RATE == 0 {
+				fips202::shake256_squeezeblocks(&mut buf, 1, &mut state);
+			}
+			let b = buf[draws % fips202::SHAKE256_RATE] as usize;
+			draws += 1;
+			if b <= i {
+				break;
+			}
+		}
+	}
+	draws
+}
+
+/// The hint bit `make_hint` computes for one coefficient.
+fn hint_bit(a0: i32, a1: i32, gamma2: i32) -> bool {
+	!(-gamma2..=gamma2).contains(&a0) || (a0 == -gamma2 && a1 != 0)
+}
+
+/// Targets for the secret-dependent routines of one parameter set, named by its module in
+/// `params` and `poly`, with the length of its challenge seed.
+macro_rules! poly_targets {
+	($set:ident, $c_dash_bytes:expr) => {
+		fn $set(report: &mut Report) {
+			use qp_rusty_crystals_dilithium::{params::$set as p, poly::$set as poly};
+
+			const ETA: i32 = p::ETA as i32;
+			const GAMMA2: i32 = p::GAMMA2 as i32;
+			/// Number of distinct high parts, (Q - 1) / (2 * GAMMA2).
+			const M: i32 = (Q - 1) / (2 * GAMMA2);
+			let name = |routine: &str| format!("{}::{routine}", stringify!($set));
+			let rng = &mut rng();
+
+			let packed = |rng: &mut StdRng| {
+				let mut buf = vec![0u8; p::POLYETA_PACKEDBYTES];
+				poly::eta_pack(&mut buf, &small_poly(rng, ETA));
+				buf
+			};
+			report.check(&name("eta_unpack"), 100_000, 16, packed(rng), packed, |buf| {
+				let mut r = Poly::default();
+				poly::eta_unpack(&mut r, buf);
+				black_box(r);
+			});
+
+			// Nibbles from 15 (ETA 2) or 9 (ETA 4) up are rejected, which only depends on the
+			// discarded values; both classes draw buffers without any, that fill all N
+			// coefficients.
+			let accept: u8 = if ETA == 2 { 15 } else { 9 };
+			let accepted = |rng: &mut StdRng| -> Vec<u8> {
+				(0..N / 2)
+					.map(|_| rng.gen_range(0..accept) | rng.gen_range(0..accept) << 4)
+					.collect()
+			};
+			report.check(&name("rej_eta"), 100_000, 8, accepted(rng), accepted, |buf| {
+				let mut a = [0i32; N];
+				black_box(poly::rej_eta(&mut a, N, buf, buf.len()));
+				black_box(a);
+			});
+
+			// c~ is only published for the attempt that is accepted; a rejected attempt's seed
+			// depends on y and stays secret. Both classes use seeds that reject nothing, the
+			// likeliest pattern, and differ only in positions and signs. Such seeds take up to a
+			// few thousand tries to find, so they come from a pool, and the fixed seed is cloned
+			// into a fresh buffer per call like the pooled ones.
+			let seeds: Vec<Vec<u8>> = (0..=POOL)
+				.map(|_| loop {
+					let seed: Vec<u8> = (0..$c_dash_bytes).map(|_| rng.gen()).collect();
+					if challenge_draws(&seed, p::TAU) == 8 + p::TAU {
+						break seed;
+					}
+				})
+				.collect();
+			let pooled = |rng: &mut StdRng| seeds[rng.gen_range(1..=POOL)].clone();
+			report.check(&name("challenge"), 100_000, 4, seeds[0].clone(), pooled, |seed| {
+				let mut c = Poly::default();
+				poly::challenge(&mut c, seed);
+				black_box(c);
+			});
+
+			// Hint bits are published in the signature; both classes share the fixed input's.
+			let hint_input = |rng: &mut StdRng| {
+				let a0 = small_poly(rng, 2 * GAMMA2 - 1);
+				let mut a1 = Poly::default();
+				a1.coeffs.iter_mut().for_each(|c| *c = rng.gen_range(0..M));
+				(a0, a1)
+			};
+			let fixed = hint_input(rng);
+			let hints: Vec<bool> =
+				(0..N).map(|i| hint_bit(fixed.0.coeffs[i], fixed.1.coeffs[i], GAMMA2)).collect();
+			let matching = |rng: &mut StdRng| {
+				let (mut a0, mut a1) = (Poly::default(), Poly::default());
+				for i in 0..N {
+					(a0.coeffs[i], a1.coeffs[i]) = loop {
+						let (x0, x1) =
+							(rng.gen_range(-2 * GAMMA2 + 1..2 * GAMMA2), rng.gen_range(0..M));
+						if hint_bit(x0, x1, GAMMA2) == hints[i] {
+							break (x0, x1);
+						}
+					};
+				}
+				(a0, a1)
+			};
+			report.check(&name("make_hint"), 100_000, 4, fixed, matching, |(a0, a1)| {
+				let mut h = Poly::default();
+				black_box(poly::make_hint(&mut h, a0, a1));
+				black_box(h);
+			});
+
+			// A coefficient at or over the bound ends the check early, which only happens to
+			// rejected z; both classes stay under it.
+			let bound = (p::GAMMA1 - p::BETA) as i32;
+			let z = |rng: &mut StdRng| small_poly(rng, bound - 1);
+			report.check(&name("chknorm"), 100_000, 16, z(rng), z, |z| {
+				black_box(qp_rusty_crystals_dilithium::poly::chknorm(z, bound));
+			});
+		}
+	};
+}
+
+poly_targets!(ml_dsa_44, params::ml_dsa_44::C_DASH_BYTES);
+poly_targets!(ml_dsa_65, params::ml_dsa_65::C_DASH_BYTES);
+poly_targets!(ml_dsa_87, params::ml_dsa_87::C_DASH_BYTES);
+poly_targets!(lvl2, params::SEEDBYTES);
+poly_targets!(lvl3, params::SEEDBYTES);
+poly_targets!(lvl5, params::SEEDBYTES);
+
+/// Hedged signing under one fixed key against keys drawn from a pool. Every signature draws
+/// fresh randomness, so the number of rejected attempts varies alike in both classes. Like
+/// `challenge`'s seeds, both classes sign with keys cloned into fresh buffers.
+fn sign<P: ParameterSet>(report: &mut Report, name: &str) {
+	let rng = &mut rng();
+	let keys: Vec<SecretKey<P>> = (0..=POOL).map(|_| SecretKey::from_seed(&rng.gen())).collect();
+	let signing = &mut StdRng::seed_from_u64(1);
+	report.check(
+		name,
+		10_000,
+		1,
+		keys[0].clone(),
+		|rng| keys[rng.gen_range(1..=POOL)].clone(),
+		|key| {
+			black_box(key.sign_with_rng(b"timing", None, signing).unwrap());
+		},
+	);
+}
@@ -0,0 +1,370 @@
+// tests/wycheproof_integration_test.rs
+
+mod helpers;
+
+use std::collections::BTreeMap;
+
+use helpers::{
+	acvp::message_prime,
+	wycheproof::{load, Outcome, TestCase, TestGroup, VectorFile},
+};
+use qp_rusty_crystals_dilithium::{
+	fips202, packing, params, polyvec, polyvec::Polyvec, sign, Error, MlDsa44, MlDsa65, MlDsa87,
+	ParameterSet,
+};
+
+type VerifyFn = fn(&[u8], &[u8], &[u8]) -> Result<(), Error>;
+type CheckFn = fn(&[u8]) -> Result<(), Error>;
+
+const N: usize = params::N as usize;
+
+/// Run every test of a vector file through `verify`, and through `check_signature`, which must
+/// reach the same verdict whenever the failure does not depend on the key.
+fn run(json: &str, verify: VerifyFn, check_signature: CheckFn) {
+	let file = load(json);
+	let mut count = 0;
+	for group in &file.test_groups {
+		for test in &group.tests {
+			let m_prime = message_prime(&test.ctx, &test.message());
+			let result = verify(&test.sig, &m_prime, &group.public_key);
+			match test.result {
+				Outcome::Valid => {
+					assert_eq!(result, Ok(()), "tcId {} ({}) rejected", test.tc_id, test.comment)
+				},
+				Outcome::Invalid => {
+					let err = result
+						.expect_err(&format!("tcId {} ({}) accepted", test.tc_id, test.comment));
+					assert_eq!(
+						Some(variant(&err)),
+						test.error,
+						"tcId {} ({}) failed with {err:?}",
+						test.tc_id,
+						test.comment
+					);
+				},
+			}
+			match result {
+				Ok(()) | Err(Error::ChallengeMismatch) => {
+					assert_eq!(check_signature(&test.sig), Ok(()), "tcId {}", test.tc_id)
+				},
+				Err(err) => assert_eq!(check_signature(&test.sig), Err(err), "tcId {}", test.tc_id),
+			}
+			count += 1;
+		}
+	}
+	assert_eq!(count, file.number_of_tests);
+}
+
+/// The name of an [Error] variant, as the `error` field of a test spells it.
+fn variant(err: &Error) -> String {
+	let debug = format!("{err:?}");
+	debug.split(|c: char| !c.is_alphanumeric()).next().unwrap().to_string()
+}
+
+#[test]
+fn ml_dsa_44_edge_cases() {
+	run(
+		include_str!("../test_vectors/wycheproof/mldsa_44_verify_test.json"),
+		sign::ml_dsa_44::verify,
+		sign::ml_dsa_44::check_signature,
+	);
+}
+
+#[test]
+fn ml_dsa_65_edge_cases() {
+	run(
+		include_str!("../test_vectors/wycheproof/mldsa_65_verify_test.json"),
+		sign::ml_dsa_65::verify,
+		sign::ml_dsa_65::check_signature,
+	);
+}
+
+#[test]
+fn ml_dsa_87_edge_cases() {
+	run(
+		include_str!("../test_vectors/wycheproof/mldsa_87_verify_test.json"),
+		sign::ml_dsa_87::verify,
+		sign::ml_dsa_87::check_signature,
+	);
+}
+
+/// Rewrite the vector files from the generator below. The expected result of every test follows
+/// from how its signature was made or altered, never from running the verifier, so a regression
+/// in verification shows up as a failing test rather than as a changed file.
+#[test]
+#[ignore = "rewrites test_vectors/wycheproof; run with --ignored after changing the generator"]
+fn regenerate_vectors() {
+	use params::{ml_dsa_44, ml_dsa_65, ml_dsa_87};
+
+	write("mldsa_44_verify_test.json", generate::<MlDsa44, { ml_dsa_44::K }, { ml_dsa_44::L }>());
+	write("mldsa_65_verify_test.json", generate::<MlDsa65, { ml_dsa_65::K }, { ml_dsa_65::L }>());
+	write("mldsa_87_verify_test.json", generate::<MlDsa87, { ml_dsa_87::K }, { ml_dsa_87::L }>());
+}
+
+fn write(name: &str, file: VectorFile) {
+	let path = format!("{}/test_vectors/wycheproof/{name}", env!("CARGO_MANIFEST_DIR"));
+	let json = serde_json::to_string_pretty(&file).unwrap();
+	std::fs::write(path, json + "\n").unwrap();
+}
+
+const NOTES: [(&str, &str); 15] = [
+	("ContextLength0", "The context string is empty."),
+	("ContextLength255", "The context string has the maximum length of 255 bytes."),
+	("ContextMismatch", "The signature was made with a different context string."),
+	("EmptyMessage", "The message is empty."),
+	("ExtremePublicKey", "Every t1 coefficient of the public key is 0 or 1023, its extremes."),
+	("HintCountAtOmega", "Exactly OMEGA hints, the most an encoding can hold."),
+	("HintCountOutOfRange", "A cumulative hint count is above OMEGA or below the previous one."),
+	("HintDuplicate", "A hint position is repeated within a polynomial."),
+	("HintPadding", "A hint position after the last hint is not zero."),
+	("HintUnsorted", "The hint positions of a polynomial are not increasing."),
+	("HugeMessage", "A 1 MiB message."),
+	("ModifiedSignature", "A valid signature with its challenge or message altered."),
+	("SignatureLength", "The signature is one byte too long or too short."),
+	("WrongPublicKey", "A valid signature checked against another public key."),
+	("ZAtBound", "A z coefficient at GAMMA1 - BETA - 1, the largest accepted, or just past it."),
+];
+
+/// Collects the tests of a group; [generate] numbers them once all groups are built.
+#[derive(Default)]
+struct Tests {
+	cases: Vec<TestCase>,
+}
+
+impl Tests {
+	/// Add a test expected to fail with `error`, or to pass if it is None.
+	fn add(
+		&mut self,
+		comment: &str,
+		flags: &[&str],
+		(msg, ctx): (&[u8], &[u8]),
+		sig: Vec<u8>,
+		error: Option<&str>,
+	) -> &mut TestCase {
+		self.cases.push(TestCase {
+			tc_id: 0,
+			comment: comment.to_string(),
+			flags: flags.iter().map(|flag| flag.to_string()).collect(),
+			msg: msg.to_vec(),
+			msg_repeat: 1,
+			ctx: ctx.to_vec(),
+			sig,
+			result: if error.is_some() { Outcome::Invalid } else { Outcome::Valid },
+			error: error.map(str::to_string),
+		});
+		self.cases.last_mut().unwrap()
+	}
+
+	fn group(self, comment: &str, public_key: &[u8]) -> TestGroup {
+		TestGroup {
+			kind: "MlDsaVerify".to_string(),
+			comment: comment.to_string(),
+			public_key: public_key.to_vec(),
+			tests: self.cases,
+		}
+	}
+}
+
+/// Deterministic ML-DSA.Sign of `msg` under `ctx`.
+fn sign_deterministic<P: ParameterSet>(sk: &[u8], msg: &[u8], ctx: &[u8]) -> Vec<u8> {
+	let mut sig = vec![0u8; P::SIGNBYTES];
+	let rnd = [0u8; params::SEEDBYTES];
+	sign::sign_internal::<P>(&mut sig, &message_prime(ctx, msg), sk, &rnd).unwrap();
+	sig
+}
+
+fn decode<P: ParameterSet, const K: usize, const L: usize>(sig: &[u8]) -> (Polyvec<L>, Polyvec<K>) {
+	let mut c = [0u8; params::C_DASH_BYTES_MAX];
+	let (mut z, mut h) = (Polyvec::default(), Polyvec::default());
+	packing::unpack_sig::<P, K, L>(&mut c, &mut z, &mut h, sig).unwrap();
+	(z, h)
+}
+
+/// Re-encode `sig` with a new z and h, keeping its challenge.
+fn encode<P: ParameterSet, const K: usize, const L: usize>(
+	sig: &[u8],
+	z: &Polyvec<L>,
+	h: &Polyvec<K>,
+) -> Vec<u8> {
+	let mut out = sig.to_vec();
+	packing::pack_sig::<P, K, L>(&mut out, None, z, h);
+	out
+}
+
+/// A key pair with s1 = 0, t0 = 0 and every coefficient of s2 equal to `s2`, so that t = s2.
+/// s2 = 0 gives a t1 of all zeroes and s2 = -1, which is q - 1, a t1 of all 1023. Both sign like
+/// any other key.
+fn degenerate_key<P: ParameterSet, const K: usize, const L: usize>(s2: i32) -> (Vec<u8>, Vec<u8>) {
+	let (rho, key) = ([0x52u8; params::SEEDBYTES], [0x4bu8; params::SEEDBYTES]);
+	let mut secret = Polyvec::<K>::default();
+	for poly in secret.vec.iter_mut() {
+		poly.coeffs = [s2; N];
+	}
+	let (mut t1, mut t0) = (secret, Polyvec::<K>::default());
+	polyvec::caddq(&mut t1);
+	polyvec::power2round(&mut t1, &mut t0);
+	assert!(t0.vec.iter().all(|poly| poly.coeffs == [0; N]));
+	assert!(t1.vec.iter().all(|poly| poly.coeffs == [if s2 == 0 { 0 } else { 1023 }; N]));
+
+	let mut pk = vec![0u8; P::PUBLICKEYBYTES];
+	packing::pack_pk::<K>(&mut pk, &rho, &t1);
+	let mut tr = [0u8; params::TR_BYTES];
+	fips202::shake256(&mut tr, params::TR_BYTES, &pk, pk.len());
+	let mut sk = vec![0u8; P::SECRETKEYBYTES];
+	packing::pack_sk::<P, K, L>(&mut sk, &rho, &tr, &key, &t0, &Polyvec::default(), &secret);
+	(pk, sk)
+}
+
+fn generate<P: ParameterSet, const K: usize, const L: usize>() -> VectorFile {
+	let mut pk = vec![0u8; P::PUBLICKEYBYTES];
+	let mut sk = vec![0u8; P::SECRETKEYBYTES];
+	sign::keypair::<P>(&mut pk, &mut sk, Some(&[0x57u8; params::SEEDBYTES])).unwrap();
+	let mut tests = Tests::default();
+
+	let sig = sign_deterministic::<P>(&sk, b"", b"");
+	tests.add(
+		"empty message and context",
+		&["EmptyMessage", "ContextLength0"],
+		(b"", b""),
+		sig,
+		None,
+	);
+	let ctx: Vec<u8> = (0..=254).collect();
+	let sig = sign_deterministic::<P>(&sk, b"context", &ctx);
+	tests.add("255-byte context", &["ContextLength255"], (b"context", &ctx), sig.clone(), None);
+	tests.add(
+		"signed with a 255-byte context, checked without",
+		&["ContextMismatch"],
+		(b"context", b""),
+		sig,
+		Some("ChallengeMismatch"),
+	);
+	let sig = sign_deterministic::<P>(&sk, &[0x61; 1 << 20], b"");
+	tests.add("1 MiB message", &["HugeMessage"], (b"a", b""), sig, None).msg_repeat = 1 << 20;
+
+	// Deterministic signatures of successive messages until one has a z coefficient at the
+	// largest accepted magnitude, which takes up to a few hundred tries.
+	let bound = (P::GAMMA1 - P::BETA) as i32;
+	let (msg, sig, (i, j)) = (0..)
+		.find_map(|n| {
+			let msg = format!("z bound {n}").into_bytes();
+			let sig = sign_deterministic::<P>(&sk, &msg, b"");
+			let (z, _) = decode::<P, K, L>(&sig);
+			let at = (0..L * N).find(|&at| z.vec[at / N].coeffs[at % N].abs() == bound - 1)?;
+			Some((msg, sig, (at / N, at % N)))
+		})
+		.unwrap();
+	let (mut z, h) = decode::<P, K, L>(&sig);
+	tests.add("z coefficient at GAMMA1 - BETA - 1", &["ZAtBound"], (&msg, b""), sig.clone(), None);
+	for (comment, value) in [
+		("z coefficient at GAMMA1 - BETA", bound),
+		("z coefficient at -(GAMMA1 - BETA)", -bound),
+		("z coefficient at GAMMA1, the largest encodable", P::GAMMA1 as i32),
+	] {
+		z.vec[i].coeffs[j] = value;
+		let bad = encode::<P, K, L>(&sig, &z, &h);
+		tests.add(comment, &["ZAtBound"], (&msg, b""), bad, Some("NormBoundExceeded"));
+	}
+
+	// The hint is OMEGA positions followed by K cumulative counts.
+	let msg = b"hints";
+	let sig = sign_deterministic::<P>(&sk, msg, b"");
+	let hint = P::SIGNBYTES - P::OMEGA - K;
+	let counts: Vec<usize> = sig[hint + P::OMEGA..].iter().map(|&n| n as usize).collect();
+	assert!(counts[K - 2] > 0 && counts[K - 1] < P::OMEGA);
+	let first = |i: usize| if i == 0 { 0 } else { counts[i - 1] };
+	let row = (0..K).find(|&i| counts[i] - first(i) >= 2).unwrap();
+	let start = hint + first(row);
+
+	let (z, mut h) = decode::<P, K, L>(&sig);
+	let mut total = counts[K - 1];
+	for at in 0..K * N {
+		if total < P::OMEGA && h.vec[at / N].coeffs[at % N] == 0 {
+			h.vec[at / N].coeffs[at % N] = 1;
+			total += 1;
+		}
+	}
+	let full = encode::<P, K, L>(&sig, &z, &h);
+	let comment = "OMEGA hints, correctly encoded but changing w1";
+	tests.add(comment, &["HintCountAtOmega"], (msg, b""), full.clone(), Some("ChallengeMismatch"));
+	let mut bad = full;
+	bad[P::SIGNBYTES - 1] = P::OMEGA as u8 + 1;
+	let comment = "last hint count OMEGA + 1";
+	tests.add(comment, &["HintCountOutOfRange"], (msg, b""), bad, Some("HintCountOutOfRange"));
+	let mut bad = sig.clone();
+	bad[P::SIGNBYTES - 1] = counts[K - 2] as u8 - 1;
+	let comment = "last hint count below the previous one";
+	tests.add(comment, &["HintCountOutOfRange"], (msg, b""), bad, Some("HintCountOutOfRange"));
+	let mut bad = sig.clone();
+	bad[start + 1] = bad[start];
+	tests.add("repeated hint position", &["HintDuplicate"], (msg, b""), bad, Some("UnsortedHint"));
+	let mut bad = sig.clone();
+	bad.swap(start, start + 1);
+	tests.add("swapped hint positions", &["HintUnsorted"], (msg, b""), bad, Some("UnsortedHint"));
+	let mut bad = sig.clone();
+	bad[hint + P::OMEGA - 1] = 1;
+	let comment = "nonzero hint padding";
+	tests.add(comment, &["HintPadding"], (msg, b""), bad, Some("HintPaddingNotZero"));
+
+	let mut bad = sig.clone();
+	bad.push(0);
+	tests.add("trailing byte", &["SignatureLength"], (msg, b""), bad, Some("BadLength"));
+	let short = sig[..P::SIGNBYTES - 1].to_vec();
+	tests.add("truncated", &["SignatureLength"], (msg, b""), short, Some("BadLength"));
+	let mut bad = sig.clone();
+	bad[0] ^= 1;
+	let comment = "altered challenge";
+	tests.add(comment, &["ModifiedSignature"], (msg, b""), bad, Some("ChallengeMismatch"));
+	let comment = "altered message";
+	tests.add(comment, &["ModifiedSignature"], (b"hintz", b""), sig, Some("ChallengeMismatch"));
+	// Each degenerate key gets a valid signature of its own and the first valid signature of the
+	// group before it.
+	let mut other = tests.cases[0].clone();
+	let mut groups = vec![tests.group("key pair generated from a seed", &pk)];
+	for (s2, t1) in [(0, "t1 = 0"), (-1, "t1 = 1023")] {
+		let (pk, sk) = degenerate_key::<P, K, L>(s2);
+		let mut tests = Tests::default();
+		let sig = sign_deterministic::<P>(&sk, t1.as_bytes(), b"");
+		tests.add(t1, &["ExtremePublicKey"], (t1.as_bytes(), b""), sig, None);
+		let flags = ["ExtremePublicKey", "WrongPublicKey"];
+		let (msg, ctx) = (&other.msg[..], &other.ctx[..]);
+		let sig = other.sig.clone();
+		tests.add("signature by another key", &flags, (msg, ctx), sig, Some("ChallengeMismatch"));
+		other = tests.cases[0].clone();
+		groups.push(tests.group(&format!("public key with {t1}"), &pk));
+	}
+
+	// Number the tests across groups.
+	let mut tc_id = 0;
+	for test in groups.iter_mut().flat_map(|group| group.tests.iter_mut()) {
+		tc_id += 1;
+		test.tc_id = tc_id;
+	}
+
+	VectorFile {
+		algorithm: P::ALGORITHM.to_string(),
+		number_of_tests: tc_id,
+		header: vec![
+			"Crafted edge cases for ML-DSA verification (FIPS 204).".to_string(),
+			"Generated by regenerate_vectors in tests/wycheproof_integration_test.rs.".to_string(),
+		],
+		notes: NOTES.iter().map(|&(flag, note)| (flag.to_string(), note.to_string())).collect(),
+		test_groups: groups,
+	}
+}
+
+#[test]
+fn notes_cover_every_flag() {
+	let notes: BTreeMap<_, _> = NOTES.into_iter().collect();
+	for json in [
+		include_str!("../test_vectors/wycheproof/mldsa_44_verify_test.json"),
+		include_str!("../test_vectors/wycheproof/mldsa_65_verify_test.json"),
+		include_str!("../test_vectors/wycheproof/mldsa_87_verify_test.json"),
+	] {
+		let file = load(json);
+		assert_eq!(file.notes.len(), notes.len());
+		for test in file.test_groups.iter().flat_map(|group| &group.tests) {
+			assert!(test.flags.iter().all(|flag| notes.contains_key(flag.as_str())));
+		}
+	}
+}